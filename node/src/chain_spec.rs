@@ -1,12 +1,14 @@
+use std::path::Path;
 use sp_core::{Pair, Public, sr25519};
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature
+	AccountId, AuraConfig, Balance, BalancesConfig, GenesisConfig, GrandpaConfig, KittiesConfig,
+	SudoConfig, SystemConfig, TemplateModuleConfig, WASM_BINARY, Signature
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
 use sp_runtime::traits::{Verify, IdentifyAccount};
 use sc_service::ChainType;
+use serde::{Serialize, Deserialize};
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
@@ -14,6 +16,29 @@ use sc_service::ChainType;
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
 
+/// Kitties and proofs to create at genesis, on top of the built-in accounts.
+///
+/// Kitties are `(owner, dna, price)` and proofs are `(owner, claim)`. Owners must be
+/// endowed accounts, since both pallets lock or reserve a deposit for every item.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct GenesisExtras {
+	#[serde(default)]
+	pub kitties: Vec<(AccountId, [u8; 16], Option<Balance>)>,
+	#[serde(default)]
+	pub proofs: Vec<(AccountId, Vec<u8>)>,
+}
+
+impl GenesisExtras {
+	/// Read the extras from a JSON file.
+	pub fn from_json_file(path: &Path) -> Result<Self, String> {
+		let file = std::fs::File::open(path)
+			.map_err(|e| format!("Error opening genesis extras file `{}`: {}", path.display(), e))?;
+		serde_json::from_reader(file)
+			.map_err(|e| format!("Error parsing genesis extras file `{}`: {}", path.display(), e))
+	}
+}
+
 /// Generate a crypto pair from seed.
 pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
 	TPublic::Pair::from_string(&format!("//{}", seed), None)
@@ -39,6 +64,10 @@ pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
 }
 
 pub fn development_config() -> Result<ChainSpec, String> {
+	development_config_with(GenesisExtras::default())
+}
+
+pub fn development_config_with(extras: GenesisExtras) -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or("Development wasm binary not available".to_string())?;
 
 	Ok(ChainSpec::from_genesis(
@@ -62,6 +91,7 @@ pub fn development_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 			],
+			extras.clone(),
 			true,
 		),
 		// Bootnodes
@@ -78,6 +108,10 @@ pub fn development_config() -> Result<ChainSpec, String> {
 }
 
 pub fn local_testnet_config() -> Result<ChainSpec, String> {
+	local_testnet_config_with(GenesisExtras::default())
+}
+
+pub fn local_testnet_config_with(extras: GenesisExtras) -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or("Development wasm binary not available".to_string())?;
 
 	Ok(ChainSpec::from_genesis(
@@ -110,6 +144,7 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 			],
+			extras.clone(),
 			true,
 		),
 		// Bootnodes
@@ -131,6 +166,7 @@ fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	extras: GenesisExtras,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
@@ -153,5 +189,23 @@ fn testnet_genesis(
 			// Assign network admin rights.
 			key: root_key,
 		}),
+		pallet_template: Some(TemplateModuleConfig {
+			proofs: extras.proofs,
+		}),
+		pallet_kitties: Some(KittiesConfig {
+			kitties: extras.kitties,
+		}),
 	}
 }
+
+/// Rebuild one of the built-in chain specs with the extras read from `path`.
+pub fn with_genesis_extras(id: &str, path: &Path) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+	let extras = GenesisExtras::from_json_file(path)?;
+	Ok(match id {
+		"dev" => Box::new(development_config_with(extras)?),
+		"local_testnet" => Box::new(local_testnet_config_with(extras)?),
+		id => return Err(format!(
+			"Genesis extras can only be added to the built-in `dev` and `local` chains, not `{}`", id,
+		)),
+	})
+}
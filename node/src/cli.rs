@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use structopt::StructOpt;
 use sc_cli::RunCmd;
 
@@ -13,7 +14,7 @@ pub struct Cli {
 #[derive(Debug, StructOpt)]
 pub enum Subcommand {
 	/// Build a chain specification.
-	BuildSpec(sc_cli::BuildSpecCmd),
+	BuildSpec(BuildSpecCmd),
 
 	/// Validate blocks.
 	CheckBlock(sc_cli::CheckBlockCmd),
@@ -37,3 +38,15 @@ pub enum Subcommand {
 	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
 }
+
+#[derive(Debug, StructOpt)]
+pub struct BuildSpecCmd {
+	#[structopt(flatten)]
+	pub base: sc_cli::BuildSpecCmd,
+
+	/// JSON file with kitties and proofs to create at genesis.
+	///
+	/// Only applies to the built-in `dev` and `local` chains.
+	#[structopt(long, value_name = "PATH", parse(from_os_str))]
+	pub genesis_extras: Option<PathBuf>,
+}
@@ -67,8 +67,14 @@ pub fn run() -> sc_cli::Result<()> {
 
 	match &cli.subcommand {
 		Some(Subcommand::BuildSpec(cmd)) => {
-			let runner = cli.create_runner(cmd)?;
-			runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
+			let runner = cli.create_runner(&cmd.base)?;
+			runner.sync_run(|mut config| {
+				if let Some(path) = &cmd.genesis_extras {
+					config.chain_spec = chain_spec::with_genesis_extras(config.chain_spec.id(), path)
+						.map_err(sc_cli::Error::Input)?;
+				}
+				cmd.base.run(config.chain_spec, config.network)
+			})
 		},
 		Some(Subcommand::CheckBlock(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
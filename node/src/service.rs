@@ -127,7 +127,7 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 				deny_unsafe,
 			};
 
-			crate::rpc::create_full(deps)
+			crate::rpc::create_full::<_, _, FullBackend>(deps)
 		})
 	};
 
@@ -0,0 +1,205 @@
+//! RPC interface for the kitties pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sc_rpc_api::DenyUnsafe;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_kitties_runtime_api::KittiesApi as KittiesRuntimeApi;
+use pallet_kitties_runtime_api::{KittyInfo, KittyTraits};
+
+#[rpc]
+pub trait KittiesApi<BlockHash, AccountId, KittyIndex, Balance> {
+	/// 查询一只小猫的 DNA、拥有者、价格、父母和孩子
+	#[rpc(name = "kitties_getKitty")]
+	fn get_kitty(
+		&self,
+		id: KittyIndex,
+		at: Option<BlockHash>,
+	) -> Result<Option<KittyInfo<AccountId, KittyIndex, Balance>>>;
+
+	/// 分页查询某个账户拥有的小猫, `page` 从 0 开始
+	#[rpc(name = "kitties_getKittiesOf")]
+	fn get_kitties_of(
+		&self,
+		account: AccountId,
+		page: u32,
+		at: Option<BlockHash>,
+	) -> Result<Vec<KittyInfo<AccountId, KittyIndex, Balance>>>;
+
+	/// 查询小猫往上 `generations` 代的祖先, 第 i 组是往上第 i + 1 代
+	#[rpc(name = "kitties_getAncestors")]
+	fn get_ancestors(
+		&self,
+		id: KittyIndex,
+		generations: u32,
+		at: Option<BlockHash>,
+	) -> Result<Vec<Vec<KittyIndex>>>;
+
+	/// 查询小猫的毛色、花纹、眼型和稀有度, 包括表达出来的和隐藏的性状
+	#[rpc(name = "kitties_getTraits")]
+	fn get_traits(
+		&self,
+		id: KittyIndex,
+		at: Option<BlockHash>,
+	) -> Result<Option<KittyTraits>>;
+
+	/// 查询小猫的稀有度评分, 性状越少见分数越高
+	#[rpc(name = "kitties_getRarity")]
+	fn get_rarity(
+		&self,
+		id: KittyIndex,
+		at: Option<BlockHash>,
+	) -> Result<Option<u32>>;
+
+	/// 检查小猫相关存储的一致性, 不一致时返回错误并带上发现的第一个问题.
+	/// 需要遍历整个模块的存储, 只在允许不安全 RPC 的节点上可用.
+	#[rpc(name = "kitties_tryState")]
+	fn try_state(&self, at: Option<BlockHash>) -> Result<()>;
+}
+
+/// A struct that implements the [`KittiesApi`].
+pub struct Kitties<C, B> {
+	client: Arc<C>,
+	deny_unsafe: DenyUnsafe,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Kitties<C, B> {
+	/// Create new `Kitties` with the given reference to the client.
+	pub fn new(client: Arc<C>, deny_unsafe: DenyUnsafe) -> Self {
+		Kitties { client, deny_unsafe, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// The storage failed the integrity check.
+	IntegrityError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::IntegrityError => 2,
+		}
+	}
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId, KittyIndex, Balance>
+	KittiesApi<<Block as BlockT>::Hash, AccountId, KittyIndex, Balance> for Kitties<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C: Send + Sync,
+	C::Api: KittiesRuntimeApi<Block, AccountId, KittyIndex, Balance>,
+	AccountId: Codec,
+	KittyIndex: Codec,
+	Balance: Codec,
+{
+	fn get_kitty(
+		&self,
+		id: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<KittyInfo<AccountId, KittyIndex, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.kitty(&at, id).map_err(|e| runtime_error("Unable to query kitty.", e))
+	}
+
+	fn get_kitties_of(
+		&self,
+		account: AccountId,
+		page: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<KittyInfo<AccountId, KittyIndex, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.kitties_of(&at, account, page).map_err(|e| runtime_error("Unable to query kitties of account.", e))
+	}
+
+	fn get_ancestors(
+		&self,
+		id: KittyIndex,
+		generations: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<Vec<KittyIndex>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.ancestors(&at, id, generations).map_err(|e| runtime_error("Unable to query kitty ancestors.", e))
+	}
+
+	fn get_traits(
+		&self,
+		id: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<KittyTraits>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.traits(&at, id).map_err(|e| runtime_error("Unable to query kitty traits.", e))
+	}
+
+	fn get_rarity(
+		&self,
+		id: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<u32>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.rarity(&at, id).map_err(|e| runtime_error("Unable to query kitty rarity.", e))
+	}
+
+	fn try_state(&self, at: Option<<Block as BlockT>::Hash>) -> Result<()> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.try_state(&at)
+			.map_err(|e| runtime_error("Unable to check kitties storage.", e))?
+			.map_err(|e| RpcError {
+				code: ErrorCode::ServerError(Error::IntegrityError.into()),
+				message: "Kitties storage is inconsistent.".into(),
+				data: Some(String::from_utf8_lossy(&e).into_owned().into()),
+			})
+	}
+}
@@ -0,0 +1,39 @@
+//! Runtime API definition for the kitties pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::too_many_arguments)]
+// The `unnecessary_mut_passed` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+pub use pallet_kitties::{ KittyInfo, KittyTraits };
+
+sp_api::decl_runtime_apis! {
+	pub trait KittiesApi<AccountId, KittyIndex, Balance> where
+		AccountId: Codec,
+		KittyIndex: Codec,
+		Balance: Codec,
+	{
+		/// 查询一只小猫的完整信息
+		fn kitty(id: KittyIndex) -> Option<KittyInfo<AccountId, KittyIndex, Balance>>;
+
+		/// 分页查询某个账户拥有的小猫
+		fn kitties_of(account: AccountId, page: u32) -> Vec<KittyInfo<AccountId, KittyIndex, Balance>>;
+
+		/// 查询小猫往上 `generations` 代的祖先, 每一代一组
+		fn ancestors(id: KittyIndex, generations: u32) -> Vec<Vec<KittyIndex>>;
+
+		/// 查询小猫由 DNA 解出的性状
+		fn traits(id: KittyIndex) -> Option<KittyTraits>;
+
+		/// 查询小猫的稀有度评分
+		fn rarity(id: KittyIndex) -> Option<u32>;
+
+		/// 检查小猫相关存储的一致性, 失败时返回发现的第一个问题.
+		/// 需要遍历全部存储, 只用于离线审计.
+		fn try_state() -> Result<(), Vec<u8>>;
+	}
+}
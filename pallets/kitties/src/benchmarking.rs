@@ -0,0 +1,482 @@
+//! Benchmarks for the kitties pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, account};
+use frame_support::traits::{OnFinalize, OnInitialize};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, One};
+
+const SEED: u32 = 0;
+
+fn funded_account<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+    let who = account(name, index, SEED);
+    T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value() / 2u32.into());
+    who
+}
+
+fn create_kitty<T: Trait>(owner: &T::AccountId) -> Result<T::KittyIndex, &'static str> {
+    Module::<T>::create(RawOrigin::Signed(owner.clone()).into())?;
+    Ok(Module::<T>::kitties_count() - One::one())
+}
+
+// 创建一只有 `a` 个祖先的小猫, 祖先都是新小猫, 按代逐层补满
+fn create_kitty_with_ancestors<T: Trait>(owner: &T::AccountId, a: u32) -> Result<T::KittyIndex, &'static str> {
+    let kitty_id = create_kitty::<T>(owner)?;
+    let mut lineage = sp_std::vec![kitty_id];
+    let mut next = 0;
+    while lineage.len() as u32 <= a {
+        let parent_1 = create_kitty::<T>(owner)?;
+        let parent_2 = create_kitty::<T>(owner)?;
+        Module::<T>::record_lineage(lineage[next], parent_1, parent_2);
+        lineage.push(parent_1);
+        lineage.push(parent_2);
+        next += 1;
+    }
+    Ok(kitty_id)
+}
+
+// 把小猫挂出作为种猫, 白名单有 `w` 个账户
+fn list_sire_with_whitelist<T: Trait>(
+    owner: &T::AccountId,
+    kitty_id: T::KittyIndex,
+    w: u32,
+) -> Result<(), &'static str> {
+    let whitelist = (0 .. w).map(|i| account("breeder", i, SEED)).collect();
+    Module::<T>::list_sire(RawOrigin::Signed(owner.clone()).into(), kitty_id, 100u32.into(), Some(whitelist))?;
+    Ok(())
+}
+
+// 创建一场英式拍卖, 并由 `bidder` 出价
+fn create_english_auction<T: Trait>(
+    seller: &T::AccountId,
+    bidder: &T::AccountId,
+    end_block: T::BlockNumber,
+) -> Result<T::KittyIndex, &'static str> {
+    let kitty_id = create_kitty::<T>(seller)?;
+    Module::<T>::create_auction(
+        RawOrigin::Signed(seller.clone()).into(),
+        kitty_id,
+        AuctionKind::English,
+        100u32.into(),
+        100u32.into(),
+        end_block,
+    )?;
+    Module::<T>::bid(RawOrigin::Signed(bidder.clone()).into(), kitty_id, 100u32.into())?;
+    Ok(kitty_id)
+}
+
+benchmarks! {
+    _ { }
+
+    create {
+        let caller = funded_account::<T>("caller", 0);
+        let kitty_id = Module::<T>::kitties_count();
+    }: _(RawOrigin::Signed(caller.clone()))
+    verify {
+        assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(caller));
+    }
+
+    // 双方一共有 a 个祖先, 血缘检查需要逐个读取
+    breed {
+        let a in 0 .. Module::<T>::max_ancestors(T::MinAncestryDistance::get());
+        let caller = funded_account::<T>("caller", 0);
+        let kitty_id_1 = create_kitty_with_ancestors::<T>(&caller, a / 2)?;
+        let kitty_id_2 = create_kitty_with_ancestors::<T>(&caller, a / 2)?;
+        let kitty_id = Module::<T>::kitties_count();
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id_1, kitty_id_2)
+    verify {
+        assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(caller));
+    }
+
+    // 小猫的种猫白名单有 w 个账户, 转让时需要清除
+    transfer {
+        let w in 0 .. T::MaxSireWhitelist::get();
+        let caller = funded_account::<T>("caller", 0);
+        let recipient = funded_account::<T>("recipient", 0);
+        // 让小猫位于链表中间, 移除时需要更新前后两个节点
+        create_kitty::<T>(&caller)?;
+        let kitty_id = create_kitty::<T>(&caller)?;
+        create_kitty::<T>(&caller)?;
+        create_kitty::<T>(&recipient)?;
+        list_sire_with_whitelist::<T>(&caller, kitty_id, w)?;
+    }: _(RawOrigin::Signed(caller.clone()), recipient.clone(), kitty_id)
+    verify {
+        assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(recipient));
+    }
+
+    // 由拥有者的代理转让, 需要依次检查单只小猫的授权和代理授权
+    transfer_from {
+        let w in 0 .. T::MaxSireWhitelist::get();
+        let owner = funded_account::<T>("owner", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let recipient = funded_account::<T>("recipient", 0);
+        create_kitty::<T>(&owner)?;
+        let kitty_id = create_kitty::<T>(&owner)?;
+        create_kitty::<T>(&owner)?;
+        create_kitty::<T>(&recipient)?;
+        list_sire_with_whitelist::<T>(&owner, kitty_id, w)?;
+        Module::<T>::set_approval_for_all(RawOrigin::Signed(owner.clone()).into(), caller.clone(), true)?;
+    }: _(RawOrigin::Signed(caller), owner, recipient.clone(), kitty_id)
+    verify {
+        assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(recipient));
+    }
+
+    // 由拥有者的代理修改授权
+    approve {
+        let owner = funded_account::<T>("owner", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let spender = funded_account::<T>("spender", 0);
+        let kitty_id = create_kitty::<T>(&owner)?;
+        Module::<T>::set_approval_for_all(RawOrigin::Signed(owner).into(), caller.clone(), true)?;
+    }: _(RawOrigin::Signed(caller), kitty_id, Some(spender.clone()))
+    verify {
+        assert_eq!(Module::<T>::kitty_approval(kitty_id), Some(spender));
+    }
+
+    set_approval_for_all {
+        let caller = funded_account::<T>("caller", 0);
+        let operator = funded_account::<T>("operator", 0);
+    }: _(RawOrigin::Signed(caller.clone()), operator.clone(), true)
+    verify {
+        assert!(Module::<T>::is_approved_for_all(&caller, &operator));
+    }
+
+    ask {
+        let caller = funded_account::<T>("caller", 0);
+        let kitty_id = create_kitty::<T>(&caller)?;
+        let price: BalanceOf<T> = 100u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, Some(price))
+    verify {
+        assert_eq!(Module::<T>::kitty_price(kitty_id).map(|listing| listing.price), Some(price));
+    }
+
+    buy {
+        let w in 0 .. T::MaxSireWhitelist::get();
+        let seller = funded_account::<T>("seller", 0);
+        let buyer = funded_account::<T>("buyer", 0);
+        create_kitty::<T>(&seller)?;
+        let kitty_id = create_kitty::<T>(&seller)?;
+        create_kitty::<T>(&seller)?;
+        let price: BalanceOf<T> = 100u32.into();
+        list_sire_with_whitelist::<T>(&seller, kitty_id, w)?;
+        let nonce = Module::<T>::next_listing_nonce();
+        Module::<T>::ask(RawOrigin::Signed(seller.clone()).into(), kitty_id, Some(price))?;
+    }: _(RawOrigin::Signed(buyer.clone()), kitty_id, price, nonce)
+    verify {
+        assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(buyer));
+    }
+
+    create_auction {
+        let w in 0 .. T::MaxSireWhitelist::get();
+        let caller = funded_account::<T>("caller", 0);
+        let kitty_id = create_kitty::<T>(&caller)?;
+        let end_block = frame_system::Module::<T>::block_number() + 10u32.into();
+        for i in 1 .. T::MaxAuctionsPerBlock::get() {
+            let seller = funded_account::<T>("seller", i);
+            let kitty_id = create_kitty::<T>(&seller)?;
+            Module::<T>::create_auction(
+                RawOrigin::Signed(seller).into(), kitty_id, AuctionKind::Dutch, 100u32.into(), 0u32.into(), end_block,
+            )?;
+        }
+        Module::<T>::ask(RawOrigin::Signed(caller.clone()).into(), kitty_id, Some(100u32.into()))?;
+        list_sire_with_whitelist::<T>(&caller, kitty_id, w)?;
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, AuctionKind::English, 100u32.into(), 100u32.into(), end_block)
+    verify {
+        assert!(Module::<T>::auction(kitty_id).is_some());
+    }
+
+    // 英式拍卖出价, 走退还上一个出价的分支
+    bid {
+        let seller = funded_account::<T>("seller", 0);
+        let bidder = funded_account::<T>("bidder", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let end_block = frame_system::Module::<T>::block_number() + 10u32.into();
+        let kitty_id = create_english_auction::<T>(&seller, &bidder, end_block)?;
+        let amount: BalanceOf<T> = 200u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, amount)
+    verify {
+        assert_eq!(Module::<T>::auction(kitty_id).and_then(|auction| auction.best_bid), Some((caller, amount)));
+    }
+
+    cancel_auction {
+        let caller = funded_account::<T>("caller", 0);
+        let kitty_id = create_kitty::<T>(&caller)?;
+        let end_block = frame_system::Module::<T>::block_number() + 10u32.into();
+        Module::<T>::create_auction(
+            RawOrigin::Signed(caller.clone()).into(), kitty_id, AuctionKind::English, 100u32.into(), 100u32.into(), end_block,
+        )?;
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id)
+    verify {
+        assert!(Module::<T>::auction(kitty_id).is_none());
+    }
+
+    make_offer {
+        let owner = funded_account::<T>("owner", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let kitty_id = create_kitty::<T>(&owner)?;
+        let amount: BalanceOf<T> = 100u32.into();
+        let expiry = frame_system::Module::<T>::block_number() + 10u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, amount, expiry)
+    verify {
+        assert_eq!(Module::<T>::offer(kitty_id, &caller), Some(Offer { amount, expiry }));
+    }
+
+    accept_offer {
+        let w in 0 .. T::MaxSireWhitelist::get();
+        let caller = funded_account::<T>("caller", 0);
+        let buyer = funded_account::<T>("buyer", 0);
+        create_kitty::<T>(&caller)?;
+        let kitty_id = create_kitty::<T>(&caller)?;
+        create_kitty::<T>(&caller)?;
+        list_sire_with_whitelist::<T>(&caller, kitty_id, w)?;
+        let expiry = frame_system::Module::<T>::block_number() + 10u32.into();
+        Module::<T>::make_offer(RawOrigin::Signed(buyer.clone()).into(), kitty_id, 100u32.into(), expiry)?;
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, buyer.clone())
+    verify {
+        assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(buyer));
+    }
+
+    withdraw_offer {
+        let owner = funded_account::<T>("owner", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let kitty_id = create_kitty::<T>(&owner)?;
+        let expiry = frame_system::Module::<T>::block_number() + 10u32.into();
+        Module::<T>::make_offer(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100u32.into(), expiry)?;
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id)
+    verify {
+        assert_eq!(Module::<T>::offer(kitty_id, &caller), None);
+    }
+
+    // 有名字、挂单和 w 个账户的种猫白名单的小猫位于链表中间
+    burn {
+        let w in 0 .. T::MaxSireWhitelist::get();
+        let caller = funded_account::<T>("caller", 0);
+        create_kitty::<T>(&caller)?;
+        let kitty_id = create_kitty::<T>(&caller)?;
+        create_kitty::<T>(&caller)?;
+        let name = sp_std::vec![b'k'; T::MaxNameLength::get() as usize];
+        Module::<T>::set_name(RawOrigin::Signed(caller.clone()).into(), kitty_id, name)?;
+        Module::<T>::ask(RawOrigin::Signed(caller.clone()).into(), kitty_id, Some(100u32.into()))?;
+        list_sire_with_whitelist::<T>(&caller, kitty_id, w)?;
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id)
+    verify {
+        assert_eq!(Module::<T>::kitty_owner(kitty_id), None);
+    }
+
+    // 给已经有名字的小猫改名, 需要调整质押的押金
+    set_name {
+        let caller = funded_account::<T>("caller", 0);
+        let kitty_id = create_kitty::<T>(&caller)?;
+        Module::<T>::set_name(RawOrigin::Signed(caller.clone()).into(), kitty_id, b"kitty".to_vec())?;
+        let name = sp_std::vec![b'k'; T::MaxNameLength::get() as usize];
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, name.clone())
+    verify {
+        assert_eq!(Module::<T>::kitty_name(kitty_id), Some((name, caller, T::NameDeposit::get())));
+    }
+
+    // 把有 w 个账户的白名单替换为另外 w 个账户
+    list_sire {
+        let w in 0 .. T::MaxSireWhitelist::get();
+        let caller = funded_account::<T>("caller", 0);
+        let kitty_id = create_kitty::<T>(&caller)?;
+        let old = (0 .. w).map(|i| account("old", i, SEED)).collect();
+        Module::<T>::list_sire(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100u32.into(), Some(old))?;
+        let whitelist = (0 .. w).map(|i| account("breeder", i, SEED)).collect();
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, 100u32.into(), Some(whitelist))
+    verify {
+        assert_eq!(Module::<T>::sire_listing(kitty_id), Some(SireListing { fee: 100u32.into(), restricted: true }));
+    }
+
+    // 清除有 w 个账户的白名单
+    unlist_sire {
+        let w in 0 .. T::MaxSireWhitelist::get();
+        let caller = funded_account::<T>("caller", 0);
+        let kitty_id = create_kitty::<T>(&caller)?;
+        list_sire_with_whitelist::<T>(&caller, kitty_id, w)?;
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id)
+    verify {
+        assert_eq!(Module::<T>::sire_listing(kitty_id), None);
+    }
+
+    // 使用需要检查白名单的种猫, 双方一共有 a 个祖先
+    breed_with_sire {
+        let a in 0 .. Module::<T>::max_ancestors(T::MinAncestryDistance::get());
+        let owner = funded_account::<T>("owner", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let sire_id = create_kitty_with_ancestors::<T>(&owner, a / 2)?;
+        let kitty_id = create_kitty_with_ancestors::<T>(&caller, a / 2)?;
+        Module::<T>::list_sire(
+            RawOrigin::Signed(owner.clone()).into(),
+            sire_id,
+            100u32.into(),
+            Some(sp_std::vec![caller.clone()]),
+        )?;
+        let new_kitty_id = Module::<T>::kitties_count();
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, sire_id, 100u32.into())
+    verify {
+        assert_eq!(Module::<T>::kitty_owner(new_kitty_id), Some(caller));
+    }
+
+    lease {
+        let caller = funded_account::<T>("caller", 0);
+        let lessee: T::AccountId = account("lessee", 0, SEED);
+        let kitty_id = create_kitty::<T>(&caller)?;
+        let until = frame_system::Module::<T>::block_number() + 10u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, lessee.clone(), until, 100u32.into())
+    verify {
+        assert_eq!(Module::<T>::lease_offer(kitty_id), Some(LeaseTerms { lessee, fee: 100u32.into(), until }));
+    }
+
+    // 接受出租条件时需要取消一口价挂单和有 w 个账户白名单的种猫挂单
+    accept_lease {
+        let w in 0 .. T::MaxSireWhitelist::get();
+        let owner = funded_account::<T>("owner", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let kitty_id = create_kitty::<T>(&owner)?;
+        let until = frame_system::Module::<T>::block_number() + 10u32.into();
+        Module::<T>::ask(RawOrigin::Signed(owner.clone()).into(), kitty_id, Some(100u32.into()))?;
+        list_sire_with_whitelist::<T>(&owner, kitty_id, w)?;
+        Module::<T>::lease(RawOrigin::Signed(owner.clone()).into(), kitty_id, caller.clone(), until, 100u32.into())?;
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, 100u32.into())
+    verify {
+        assert_eq!(Module::<T>::kitty_user(kitty_id), Some(caller));
+    }
+
+    // 租约进行到一半时终止, 租金需要分给双方
+    terminate_lease {
+        let caller = funded_account::<T>("caller", 0);
+        let lessee = funded_account::<T>("lessee", 0);
+        let kitty_id = create_kitty::<T>(&caller)?;
+        let now = frame_system::Module::<T>::block_number();
+        Module::<T>::lease(RawOrigin::Signed(caller.clone()).into(), kitty_id, lessee.clone(), now + 10u32.into(), 100u32.into())?;
+        Module::<T>::accept_lease(RawOrigin::Signed(lessee.clone()).into(), kitty_id, 100u32.into())?;
+        frame_system::Module::<T>::set_block_number(now + 5u32.into());
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id)
+    verify {
+        assert_eq!(Module::<T>::kitty_user(kitty_id), Some(caller));
+    }
+
+    // 结束 l 份到期的租约
+    expire_leases {
+        let l in 0 .. T::MaxLeasesPerBlock::get();
+        let until = frame_system::Module::<T>::block_number() + 10u32.into();
+        let mut kitties = Vec::new();
+        for i in 0 .. l {
+            let owner = funded_account::<T>("owner", i);
+            let lessee = funded_account::<T>("lessee", i);
+            let kitty_id = create_kitty::<T>(&owner)?;
+            Module::<T>::lease(RawOrigin::Signed(owner.clone()).into(), kitty_id, lessee.clone(), until, 100u32.into())?;
+            Module::<T>::accept_lease(RawOrigin::Signed(lessee).into(), kitty_id, 100u32.into())?;
+            kitties.push((kitty_id, owner));
+        }
+    }: {
+        Module::<T>::on_initialize(until);
+    }
+    verify {
+        for (kitty_id, owner) in kitties {
+            assert_eq!(Module::<T>::kitty_user(kitty_id), Some(owner));
+        }
+    }
+
+    // 清理旧版本遗留的锁, 一个账户上有 n 个锁
+    sweep_legacy_locks {
+        let n in 0 .. LEGACY_LOCK_SWEEP_LIMIT;
+        let caller = funded_account::<T>("caller", 0);
+        for i in 0 .. n {
+            T::Currency::set_lock(
+                Module::<T>::legacy_lock_id(&caller, i.into()),
+                &caller,
+                1u32.into(),
+                WithdrawReasons::all(),
+            );
+        }
+        KittiesCount::<T>::put(T::KittyIndex::from(n));
+        let key = <frame_system::Account<T> as frame_support::storage::StorageMap<_, _>>::hashed_key_for(&caller);
+        LegacyLockSweep::<T>::put((key, T::KittyIndex::zero()));
+    }: {
+        Module::<T>::sweep_legacy_locks(n);
+    }
+    verify {
+        let balance = T::Currency::free_balance(&caller);
+        assert!(T::Currency::ensure_can_withdraw(&caller, balance, WithdrawReasons::all(), Zero::zero()).is_ok());
+    }
+
+    // 解除 o 个过期报价的质押
+    expire_offers {
+        let o in 0 .. T::MaxOffersPerBlock::get();
+        let owner = funded_account::<T>("owner", 0);
+        let kitty_id = create_kitty::<T>(&owner)?;
+        let expiry = frame_system::Module::<T>::block_number() + 10u32.into();
+        for i in 0 .. o {
+            let bidder = funded_account::<T>("bidder", i);
+            Module::<T>::make_offer(RawOrigin::Signed(bidder).into(), kitty_id, 100u32.into(), expiry)?;
+        }
+    }: {
+        Module::<T>::on_initialize(expiry);
+    }
+    verify {
+        assert!(Module::<T>::offer_expiries(expiry).is_empty());
+    }
+
+    // 结算 a 场成交的英式拍卖
+    on_initialize {
+        let a in 0 .. T::MaxAuctionsPerBlock::get();
+        let end_block = frame_system::Module::<T>::block_number() + 10u32.into();
+        let mut kitties = Vec::new();
+        for i in 0 .. a {
+            let seller = funded_account::<T>("seller", i);
+            let bidder = funded_account::<T>("bidder", i);
+            kitties.push((create_english_auction::<T>(&seller, &bidder, end_block)?, bidder));
+        }
+    }: {
+        Module::<T>::on_initialize(end_block);
+        Module::<T>::on_finalize(end_block);
+    }
+    verify {
+        for (kitty_id, bidder) in kitties {
+            assert_eq!(Module::<T>::kitty_owner(kitty_id), Some(bidder));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{new_test_ext, Test};
+    use frame_support::assert_ok;
+
+    #[test]
+    fn test_benchmarks() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_create::<Test>());
+            assert_ok!(test_benchmark_breed::<Test>());
+            assert_ok!(test_benchmark_transfer::<Test>());
+            assert_ok!(test_benchmark_transfer_from::<Test>());
+            assert_ok!(test_benchmark_approve::<Test>());
+            assert_ok!(test_benchmark_set_approval_for_all::<Test>());
+            assert_ok!(test_benchmark_ask::<Test>());
+            assert_ok!(test_benchmark_buy::<Test>());
+            assert_ok!(test_benchmark_create_auction::<Test>());
+            assert_ok!(test_benchmark_bid::<Test>());
+            assert_ok!(test_benchmark_cancel_auction::<Test>());
+            assert_ok!(test_benchmark_make_offer::<Test>());
+            assert_ok!(test_benchmark_accept_offer::<Test>());
+            assert_ok!(test_benchmark_withdraw_offer::<Test>());
+            assert_ok!(test_benchmark_burn::<Test>());
+            assert_ok!(test_benchmark_set_name::<Test>());
+            assert_ok!(test_benchmark_list_sire::<Test>());
+            assert_ok!(test_benchmark_unlist_sire::<Test>());
+            assert_ok!(test_benchmark_breed_with_sire::<Test>());
+            assert_ok!(test_benchmark_lease::<Test>());
+            assert_ok!(test_benchmark_accept_lease::<Test>());
+            assert_ok!(test_benchmark_terminate_lease::<Test>());
+            assert_ok!(test_benchmark_expire_leases::<Test>());
+            assert_ok!(test_benchmark_sweep_legacy_locks::<Test>());
+            assert_ok!(test_benchmark_expire_offers::<Test>());
+            assert_ok!(test_benchmark_on_initialize::<Test>());
+        });
+    }
+}
@@ -0,0 +1,238 @@
+//! 小猫 DNA 的解读.
+//!
+//! DNA 的前 8 个字节是 4 对等位基因, 每对的两个字节分别来自父母:
+//!
+//! | 字节  | 性状      |
+//! |-------|-----------|
+//! | 0, 1  | 毛色      |
+//! | 2, 3  | 花纹      |
+//! | 4, 5  | 眼型      |
+//! | 6, 7  | 稀有度    |
+//! | 8..16 | 外观细节  |
+//!
+//! 每个等位基因字节的最高位表示显性, 低 7 位决定性状的取值.
+//! 一对等位基因中显性的那个表达出来; 两个都是显性或者都是隐性时, 取值较小的那个表达出来.
+
+use codec::{ Encode, Decode };
+use sp_runtime::{ Perbill, RuntimeDebug };
+#[cfg(feature = "std")]
+use serde::{ Serialize, Deserialize };
+
+/// 等位基因的显性标记
+pub const DOMINANT: u8 = 0x80;
+
+/// 成对的等位基因数量, 之后的字节是外观细节
+pub const GENE_PAIRS: usize = 4;
+
+/// 毛色
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+pub enum Color {
+    Black,
+    White,
+    Ginger,
+    Grey,
+    Cream,
+    Calico,
+    Lilac,
+    Cinnamon,
+}
+
+/// 花纹
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+pub enum Pattern {
+    Solid,
+    Tabby,
+    Spotted,
+    Bicolor,
+    Tortoiseshell,
+    Colorpoint,
+}
+
+/// 眼型
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+pub enum EyeShape {
+    Round,
+    Almond,
+    Slanted,
+    Wide,
+}
+
+/// 稀有度等级
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+pub enum RarityTier {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+/// 可以从等位基因的低 7 位解出的性状
+pub trait Phenotype: Copy + Ord {
+    fn from_allele(value: u8) -> Self;
+}
+
+impl Phenotype for Color {
+    fn from_allele(value: u8) -> Self {
+        match value % 8 {
+            0 => Color::Black,
+            1 => Color::White,
+            2 => Color::Ginger,
+            3 => Color::Grey,
+            4 => Color::Cream,
+            5 => Color::Calico,
+            6 => Color::Lilac,
+            _ => Color::Cinnamon,
+        }
+    }
+}
+
+impl Phenotype for Pattern {
+    fn from_allele(value: u8) -> Self {
+        match value % 6 {
+            0 => Pattern::Solid,
+            1 => Pattern::Tabby,
+            2 => Pattern::Spotted,
+            3 => Pattern::Bicolor,
+            4 => Pattern::Tortoiseshell,
+            _ => Pattern::Colorpoint,
+        }
+    }
+}
+
+impl Phenotype for EyeShape {
+    fn from_allele(value: u8) -> Self {
+        match value % 4 {
+            0 => EyeShape::Round,
+            1 => EyeShape::Almond,
+            2 => EyeShape::Slanted,
+            _ => EyeShape::Wide,
+        }
+    }
+}
+
+impl Phenotype for RarityTier {
+    // 128 个取值中 64 个普通, 32 个少见, 20 个稀有, 10 个史诗, 2 个传说
+    fn from_allele(value: u8) -> Self {
+        match value {
+            0..=63 => RarityTier::Common,
+            64..=95 => RarityTier::Uncommon,
+            96..=115 => RarityTier::Rare,
+            116..=125 => RarityTier::Epic,
+            _ => RarityTier::Legendary,
+        }
+    }
+}
+
+/// 一对等位基因解出的性状
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct Gene<T> {
+    /// 表达出来的性状
+    pub expressed: T,
+    /// 携带但没有表达的性状, 可能遗传给后代
+    pub hidden: T,
+    /// 表达出来的等位基因是否是显性
+    pub dominant: bool,
+}
+
+impl<T: Phenotype> Gene<T> {
+    /// 从一对等位基因解出性状
+    pub fn from_alleles(a: u8, b: u8) -> Self {
+        let (expressed, hidden) = if expresses_first(a, b) { (a, b) } else { (b, a) };
+        Gene {
+            expressed: T::from_allele(expressed & !DOMINANT),
+            hidden: T::from_allele(hidden & !DOMINANT),
+            dominant: expressed & DOMINANT != 0,
+        }
+    }
+}
+
+// 一对等位基因中 `a` 是否表达出来
+fn expresses_first(a: u8, b: u8) -> bool {
+    match (a & DOMINANT != 0, b & DOMINANT != 0) {
+        (true, false) => true,
+        (false, true) => false,
+        _ => a & !DOMINANT <= b & !DOMINANT,
+    }
+}
+
+/// 从 DNA 解出的小猫性状
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct KittyTraits {
+    pub color: Gene<Color>,
+    pub pattern: Gene<Pattern>,
+    pub eye_shape: Gene<EyeShape>,
+    pub rarity: Gene<RarityTier>,
+}
+
+impl KittyTraits {
+    /// 解读一段 DNA
+    pub fn from_dna(dna: &[u8; 16]) -> Self {
+        KittyTraits {
+            color: Gene::from_alleles(dna[0], dna[1]),
+            pattern: Gene::from_alleles(dna[2], dna[3]),
+            eye_shape: Gene::from_alleles(dna[4], dna[5]),
+            rarity: Gene::from_alleles(dna[6], dna[7]),
+        }
+    }
+
+    /// 表达出来的性状, 每项是 (性状种类, 取值), 种类按 DNA 中的顺序从 0 开始编号
+    pub fn expressed(&self) -> [(u8, u8); GENE_PAIRS] {
+        [
+            (0, self.color.expressed as u8),
+            (1, self.pattern.expressed as u8),
+            (2, self.eye_shape.expressed as u8),
+            (3, self.rarity.expressed as u8),
+        ]
+    }
+}
+
+/// 由父母的 DNA 生成孩子的 DNA
+///
+/// 每对等位基因从父母各继承一个, 继承哪一个由 `selector` 的最低位决定, 显隐性随等位基因一起遗传.
+/// 外观细节的字节按 `selector` 的位混合.
+pub fn combine_dna(dna1: &[u8; 16], dna2: &[u8; 16], selector: &[u8; 16]) -> [u8; 16] {
+    let mut new_dna = [0u8; 16];
+
+    for pair in 0..GENE_PAIRS {
+        let i = pair * 2;
+        new_dna[i] = dna1[i + (selector[i] & 1) as usize];
+        new_dna[i + 1] = dna2[i + (selector[i + 1] & 1) as usize];
+    }
+
+    for i in GENE_PAIRS * 2..new_dna.len() {
+        new_dna[i] = (selector[i] & dna1[i]) | (!selector[i] & dna2[i]);
+    }
+
+    new_dna
+}
+
+/// 让基因以 `rate` 的概率突变, 返回发生突变的等位基因对数量
+///
+/// 每对等位基因用 `entropy` 的 4 个字节决定是否突变; 突变时随机翻转其中一个等位基因的若干位,
+/// 显隐性也可能随之改变. 外观细节不会突变.
+pub fn mutate(dna: &mut [u8; 16], rate: Perbill, entropy: &[u8; 32]) -> u32 {
+    let mut mutations = 0;
+
+    for pair in 0..GENE_PAIRS {
+        let mut roll = [0u8; 4];
+        roll.copy_from_slice(&entropy[pair * 4..pair * 4 + 4]);
+        let roll = Perbill::from_parts(u32::from_le_bytes(roll) % 1_000_000_000);
+        if roll >= rate {
+            continue;
+        }
+
+        let choice = entropy[16 + pair * 2];
+        let flips = entropy[16 + pair * 2 + 1] | 1;
+        dna[pair * 2 + (choice & 1) as usize] ^= flips;
+        mutations += 1;
+    }
+
+    mutations
+}
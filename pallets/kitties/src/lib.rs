@@ -1,17 +1,29 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use frame_support::{
-    decl_module, decl_storage, decl_event, decl_error, dispatch, ensure, Parameter,
-    traits::{Randomness, Currency, LockableCurrency, ExistenceRequirement, WithdrawReason, WithdrawReasons},
+    decl_module, decl_storage, decl_event, decl_error, dispatch, ensure, transactional, Parameter,
+    storage::{ IterableStorageMap, IterableStorageDoubleMap },
+    traits::{
+        Get, Randomness, Currency, LockableCurrency, LockIdentifier, ExistenceRequirement,
+        ReservableCurrency, BalanceStatus, Imbalance, OnUnbalanced, WithdrawReason, WithdrawReasons,
+    },
+    weights::Weight,
 };
 use frame_system::ensure_signed;
-use sp_io::hashing::{ twox_64, blake2_128 };
+use sp_io::hashing::{ twox_64, blake2_128, blake2_256 };
 use codec::{ Encode, Decode };
-use sp_runtime::{ DispatchError, traits::{ AtLeast32Bit, Bounded, Member } };
+use sp_runtime::{
+    DispatchError, DispatchResult, Perbill, RuntimeDebug,
+    traits::{ AtLeast32Bit, Bounded, Member, One, Saturating, Zero },
+};
 use crate::linked_item::{ LinkedList, LinkedItem };
-use sp_std::prelude::*;
+use sp_std::{ prelude::*, collections::btree_set::BTreeSet };
+#[cfg(feature = "std")]
+use serde::{ Serialize, Deserialize };
 
-mod linked_item;
+pub mod linked_item;
+pub mod genes;
+pub mod nft;
 
 #[cfg(test)]
 mod mock;
@@ -19,23 +31,187 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
-#[derive(Encode, Decode)]
+mod benchmarking;
+pub mod weights;
+
+pub use weights::WeightInfo;
+pub use genes::KittyTraits;
+pub use nft::UniqueAssets;
+
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
 pub struct Kitty {
     pub dna: [u8; 16],
+    /// 代数, 创建的小猫是第 0 代, 繁殖出来的小猫比父母中较大的代数多 1
+    pub generation: u32,
+}
+
+/// 拍卖的方式
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum AuctionKind {
+    /// 英式拍卖, 出价递增, 结束时价高者得
+    English,
+    /// 荷兰式拍卖, 价格从起拍价每个区块递减到保留价, 第一个出价的人买下
+    Dutch,
+}
+
+/// 一口价挂单. 买家需要带上 `nonce`, 防止拥有者在买家看到之后修改价格
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct Listing<Balance> {
+    pub price: Balance,
+    /// 每次挂单或者改价都会分配一个新的 nonce
+    pub nonce: u64,
 }
 
-pub const LOCK_AMOUNT: u32 = 5;
+/// 一场正在进行的拍卖
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Auction<AccountId, Balance, BlockNumber> {
+    pub seller: AccountId,
+    pub kind: AuctionKind,
+    /// 英式拍卖的最低出价, 荷兰式拍卖的起始价格
+    pub start_price: Balance,
+    /// 英式拍卖成交的最低价格, 荷兰式拍卖最终降到的价格
+    pub reserve: Balance,
+    pub start_block: BlockNumber,
+    pub end_block: BlockNumber,
+    /// 英式拍卖当前的最高出价, 出价的金额被质押
+    pub best_bid: Option<(AccountId, Balance)>,
+}
+
+/// 对一只小猫的报价, 报价的金额被质押
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Offer<Balance, BlockNumber> {
+    pub amount: Balance,
+    /// 报价在这个区块开始时过期并解除质押
+    pub expiry: BlockNumber,
+}
+
+/// 种猫挂单, 其他账户支付配种费之后可以用这只小猫繁殖
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct SireListing<Balance> {
+    pub fee: Balance,
+    /// 为 true 时只有 `SireApprovals` 中的账户可以使用
+    pub restricted: bool,
+}
+
+/// 出租的条件, 拥有者提出之后由承租人接受
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct LeaseTerms<AccountId, Balance, BlockNumber> {
+    pub lessee: AccountId,
+    /// 租金, 租约期间从承租人的余额中质押
+    pub fee: Balance,
+    /// 租约在这个区块开始时结束
+    pub until: BlockNumber,
+}
+
+/// 一份生效中的租约. 租约期间承租人是小猫的使用者, 可以用它繁殖, 但是不能转让或者出售
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Lease<AccountId, Balance, BlockNumber> {
+    pub lessee: AccountId,
+    pub fee: Balance,
+    pub start: BlockNumber,
+    pub until: BlockNumber,
+}
+
+/// 存储格式的版本
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+    /// `Kitty` 只有 `dna`, 孩子和配偶没有写入存储
+    V1_0_0,
+    /// `Kitty` 记录代数, 孩子和配偶存在 double map 中
+    V2_0_0,
+    /// 每个账户只有一个按小猫数量计算的锁
+    V3_0_0,
+    /// `KittyPrices` 存储带 nonce 的 `Listing`
+    V4_0_0,
+    /// `TraitCounts` 统计了每只小猫表达出的性状
+    V5_0_0,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1_0_0
+    }
+}
+
+/// 对外查询用的小猫完整信息
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub struct KittyInfo<AccountId, KittyIndex, Balance> {
+    pub id: KittyIndex,
+    pub dna: [u8; 16],
+    pub generation: u32,
+    pub owner: AccountId,
+    /// None 表示没有出售
+    pub price: Option<Balance>,
+    /// 购买时需要带上的挂单 nonce
+    pub listing_nonce: Option<u64>,
+    /// None 表示不是繁殖出来的小猫
+    pub parents: Option<(KittyIndex, KittyIndex)>,
+    pub children: Vec<KittyIndex>,
+    pub name: Option<Vec<u8>>,
+    /// None 表示没有出租
+    pub lessee: Option<AccountId>,
+}
+
+/// 小猫押金使用的锁, 每个账户只有一个
+const KITTIES_ID: LockIdentifier = *b"kitties ";
+
+/// 每个区块清理旧版本遗留锁的步数上限, 每解除一个锁或者换一个账户算一步
+const LEGACY_LOCK_SWEEP_LIMIT: u32 = 500;
+
+/// `kitties_of` 每页返回的小猫数量
+pub const KITTIES_PAGE_SIZE: u32 = 20;
+
+/// `ancestors` 最多查询的代数
+pub const MAX_ANCESTRY_DEPTH: u32 = 8;
 
 pub trait Trait: frame_system::Trait {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
     type KittyIndex: Parameter + Member + AtLeast32Bit + Bounded + Default + Copy;
-    type Currency: LockableCurrency<Self::AccountId, Moment=Self::BlockNumber>;
+    type Currency: LockableCurrency<Self::AccountId, Moment=Self::BlockNumber> + ReservableCurrency<Self::AccountId>;
     type Randomness: Randomness<Self::Hash>;
+    /// 繁殖双方的血缘距离下限: 双方本身以及往上这么多代的祖先不能有重合.
+    /// 为 1 时禁止和父母、兄弟姐妹繁殖. 超过 `MAX_ANCESTRY_DEPTH` 的部分不检查.
+    type MinAncestryDistance: Get<u32>;
+    /// 第 0 代小猫繁殖之后的冷却区块数, 第 n 代小猫的冷却时间是它的 n + 1 倍
+    type BreedingCooldown: Get<Self::BlockNumber>;
+    /// 同一个区块最多结束的拍卖数量
+    type MaxAuctionsPerBlock: Get<u32>;
+    /// 同一个区块最多过期的报价数量
+    type MaxOffersPerBlock: Get<u32>;
+    /// 小猫成交价 (`buy`, 拍卖和接受报价) 中支付给市场的手续费比例
+    type MarketplaceFee: Get<Perbill>;
+    /// 处理市场手续费, 例如转入国库
+    type Treasury: OnUnbalanced<NegativeImbalanceOf<Self>>;
+    /// 小猫创建或繁殖时记录的创作者版税比例, 之后修改只影响新的小猫
+    type CreatorRoyalty: Get<Perbill>;
+    /// 每拥有一只小猫需要锁定的押金
+    type KittyDeposit: Get<BalanceOf<Self>>;
+    /// 繁殖时每对等位基因发生突变的概率
+    type MutationRate: Get<Perbill>;
+    /// 小猫名字的最大字节数
+    type MaxNameLength: Get<u32>;
+    /// 给小猫起名需要质押的押金
+    type NameDeposit: Get<BalanceOf<Self>>;
+    /// 种猫白名单的最大账户数. 转让、出售、销毁小猫等会清除白名单的调用按这个上限计算权重
+    type MaxSireWhitelist: Get<u32>;
+    /// 同一个区块最多结束的租约数量
+    type MaxLeasesPerBlock: Get<u32>;
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
 }
 
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+    <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::NegativeImbalance;
+type AuctionOf<T> = Auction<<T as frame_system::Trait>::AccountId, BalanceOf<T>, <T as frame_system::Trait>::BlockNumber>;
+type OfferOf<T> = Offer<BalanceOf<T>, <T as frame_system::Trait>::BlockNumber>;
+type LeaseTermsOf<T> = LeaseTerms<<T as frame_system::Trait>::AccountId, BalanceOf<T>, <T as frame_system::Trait>::BlockNumber>;
+type LeaseOf<T> = Lease<<T as frame_system::Trait>::AccountId, BalanceOf<T>, <T as frame_system::Trait>::BlockNumber>;
 type KittyLinkedItem<T> = LinkedItem<<T as Trait>::KittyIndex>;
-type OwnedKittiesList<T> = LinkedList<OwnedKitties<T>, <T as frame_system::Trait>::AccountId, <T as Trait>::KittyIndex>;
+type OwnedKittiesList<T> =
+    LinkedList<OwnedKitties<T>, OwnedKittiesCount<T>, <T as frame_system::Trait>::AccountId, <T as Trait>::KittyIndex>;
 
 decl_storage! {
     trait Store for Module<T: Trait> as Kitties {
@@ -54,20 +230,93 @@ decl_storage! {
 		pub KittyOwners get(fn kitty_owner):
 		    map hasher(blake2_128_concat) T::KittyIndex => Option<T::AccountId>;
 
+		/// 小猫的创作者以及创建时的版税比例. 没有记录的小猫不支付版税.
+		pub KittyCreators get(fn kitty_creator):
+		    map hasher(blake2_128_concat) T::KittyIndex => Option<(T::AccountId, Perbill)>;
+
+		/// 每个账户拥有的小猫数量, 用于计算锁定的押金. 由 `OwnedKittiesList` 维护.
+		pub OwnedKittiesCount get(fn owned_kitties_count):
+		    map hasher(blake2_128_concat) T::AccountId => u32;
+
         /// 存储猫的父母
         pub KittyParents get(fn kitty_parents):
             map hasher(blake2_128_concat) T::KittyIndex => (T::KittyIndex, T::KittyIndex);
 
-        /// 存储猫的孩子
-        pub KittyChildren get(fn kitty_children):
-            map hasher(blake2_128_concat) T::KittyIndex => Vec<T::KittyIndex>;
+        /// 存储猫的孩子, 通过 `kitty_children` 查询
+        pub KittyChildren:
+            double_map hasher(blake2_128_concat) T::KittyIndex, hasher(blake2_128_concat) T::KittyIndex => ();
+
+        /// 存储猫的配偶以及一起繁殖的次数, 通过 `kitty_partners` 查询
+        pub KittyPartners:
+            double_map hasher(blake2_128_concat) T::KittyIndex, hasher(blake2_128_concat) T::KittyIndex => u32;
 
-        /// 存储猫的配偶列表
-        pub KittyPartners get(fn kitty_partners):
-            map hasher(blake2_128_concat) T::KittyIndex => Vec<T::KittyIndex>;
+		/// 小猫下一次可以繁殖的区块
+		pub NextBreeding get(fn next_breeding):
+		    map hasher(blake2_128_concat) T::KittyIndex => T::BlockNumber;
 
 		/// 获取Kitty价格. None意味着没有出售.
-		pub KittyPrices get(fn kitty_price): map hasher(blake2_128_concat) T::KittyIndex => Option<BalanceOf<T>>;
+		pub KittyPrices get(fn kitty_price): map hasher(blake2_128_concat) T::KittyIndex => Option<Listing<BalanceOf<T>>>;
+
+		/// 下一个挂单使用的 nonce
+		pub NextListingNonce get(fn next_listing_nonce): u64;
+
+		/// 正在拍卖的小猫
+		pub Auctions get(fn auction): map hasher(blake2_128_concat) T::KittyIndex => Option<AuctionOf<T>>;
+
+		/// 每个区块结束的拍卖
+		pub AuctionsEnding get(fn auctions_ending):
+		    map hasher(twox_64_concat) T::BlockNumber => Vec<T::KittyIndex>;
+
+		/// 对小猫的报价, 每个账户对每只小猫最多一个
+		pub Offers get(fn offer):
+		    double_map hasher(blake2_128_concat) T::KittyIndex, hasher(blake2_128_concat) T::AccountId => Option<OfferOf<T>>;
+
+		/// 每个区块过期的报价
+		pub OfferExpiries get(fn offer_expiries):
+		    map hasher(twox_64_concat) T::BlockNumber => Vec<(T::KittyIndex, T::AccountId)>;
+
+		/// 单只小猫被授权的账户, 小猫转移时清除
+		pub KittyApprovals get(fn kitty_approval): map hasher(blake2_128_concat) T::KittyIndex => Option<T::AccountId>;
+
+		/// 拥有者授权可以处理它所有小猫的代理账户
+		pub OperatorApprovals get(fn is_approved_for_all):
+		    double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::AccountId => bool;
+
+		/// 小猫的名字, 以及起名的账户和它质押的押金
+		pub KittyNames get(fn kitty_name):
+		    map hasher(blake2_128_concat) T::KittyIndex => Option<(Vec<u8>, T::AccountId, BalanceOf<T>)>;
+
+		/// 每种性状的每个取值有多少只小猫表达出来, 键是 `KittyTraits::expressed` 中的 (性状种类, 取值)
+		pub TraitCounts get(fn trait_count):
+		    double_map hasher(twox_64_concat) u8, hasher(twox_64_concat) u8 => u32;
+
+		/// 挂出的种猫, 小猫转移或者销毁时清除
+		pub SireListings get(fn sire_listing):
+		    map hasher(blake2_128_concat) T::KittyIndex => Option<SireListing<BalanceOf<T>>>;
+
+		/// 受限的种猫挂单允许使用的账户, 和挂单一起清除
+		pub SireApprovals get(fn is_sire_approved):
+		    double_map hasher(blake2_128_concat) T::KittyIndex, hasher(blake2_128_concat) T::AccountId => bool;
+
+		/// 拥有者提出、等待承租人接受的出租条件, 小猫转移时清除
+		pub LeaseOffers get(fn lease_offer): map hasher(blake2_128_concat) T::KittyIndex => Option<LeaseTermsOf<T>>;
+
+		/// 生效中的租约
+		pub Leases get(fn active_lease): map hasher(blake2_128_concat) T::KittyIndex => Option<LeaseOf<T>>;
+
+		/// 每个区块结束的租约
+		pub LeasesEnding get(fn leases_ending):
+		    map hasher(twox_64_concat) T::BlockNumber => Vec<T::KittyIndex>;
+
+		/// 被其他模块通过 `UniqueAssets::lock` 锁定的小猫, 锁定期间拥有者不变, 但不能转移、出售、拍卖、出租或者销毁
+		pub LockedKitties get(fn kitty_locked): map hasher(blake2_128_concat) T::KittyIndex => bool;
+
+		/// 清理旧版本遗留锁的进度: (下一个账户在 `frame_system::Account` 中的存储键, 下一个小猫编号).
+		/// v3 迁移时设置, 所有账户处理完之后删除.
+		pub LegacyLockSweep get(fn legacy_lock_sweep): Option<(Vec<u8>, T::KittyIndex)>;
+
+		/// 存储格式的版本
+		StorageVersion build(|_| Releases::V5_0_0): Releases;
 
 		// pub MemberScore get(fn member_score):
 		//     double_map hasher(blake2_128_concat) GroupIndex, hasher(blake2_128_concat) T::AccountId => u32;
@@ -75,6 +324,21 @@ decl_storage! {
 		// pub GroupMembership get(fn group_membership):
 		//     map hasher(blake2_128_concat) T::AccountId => GroupIndex;
     }
+    add_extra_genesis {
+		/// 创世时创建的第 0 代小猫: (拥有者, DNA, 挂单价格), 拥有者需要有足够的余额锁定押金
+		config(kitties): Vec<(T::AccountId, [u8; 16], Option<BalanceOf<T>>)>;
+		build(|config: &GenesisConfig<T>| {
+			for (owner, dna, price) in &config.kitties {
+				let kitty_id = Module::<T>::next_kitty_id().expect("too many genesis kitties");
+				Module::<T>::ensure_can_hold(owner, Zero::zero())
+					.expect("genesis kitty owner cannot afford the deposit");
+				Module::<T>::insert_kitty(owner, kitty_id, Kitty { dna: *dna, generation: 0 });
+				if let Some(price) = price {
+					<KittyPrices<T>>::insert(kitty_id, Listing { price: *price, nonce: Module::<T>::take_listing_nonce() });
+				}
+			}
+		});
+    }
 }
 
 decl_error! {
@@ -86,6 +350,74 @@ decl_error! {
 		NotForSale,
 		PriceTooLow,
 		BalanceNotEnough,
+		/// 挂单在买家看到之后被修改过
+		ListingChanged,
+		/// 不能购买自己的小猫
+		BuyOwnKitty,
+		/// 繁殖双方的血缘太近
+		TooCloselyRelated,
+		/// 小猫还在繁殖冷却中
+		KittyOnCooldown,
+		/// 小猫正在拍卖
+		KittyInAuction,
+		/// 拍卖不存在
+		AuctionNotExist,
+		/// 拍卖结束的区块必须在将来
+		InvalidAuctionEnd,
+		/// 荷兰式拍卖的保留价不能高于起始价
+		InvalidReservePrice,
+		/// 同一个区块结束的拍卖太多
+		TooManyAuctions,
+		/// 出价低于起拍价、当前最高价或者当前价格
+		BidTooLow,
+		/// 不能为自己的小猫出价
+		BidOnOwnKitty,
+		/// 已经有人出价的拍卖不能取消
+		AuctionHasBids,
+		/// 不能对自己的小猫报价
+		OfferOnOwnKitty,
+		/// 已经对这只小猫报过价
+		OfferAlreadyExist,
+		/// 报价不存在
+		OfferNotExist,
+		/// 报价过期的区块必须在将来
+		InvalidOfferExpiry,
+		/// 同一个区块过期的报价太多
+		TooManyOffers,
+		/// 名字超过了最大长度
+		NameTooLong,
+		/// 小猫没有名字
+		NameNotSet,
+		/// 不能授权给拥有者自己
+		ApproveToOwner,
+		/// `from` 不是小猫的拥有者
+		WrongOwner,
+		/// 小猫没有作为种猫挂出
+		NotSireListed,
+		/// 配种费超过了愿意支付的上限
+		SireFeeTooHigh,
+		/// 不在种猫的白名单中
+		NotOnSireWhitelist,
+		/// 种猫白名单的账户太多
+		TooManySireApprovals,
+		/// 小猫正在出租
+		KittyLeased,
+		/// 不能出租给拥有者自己
+		LeaseToOwner,
+		/// 租约结束的区块必须在将来
+		InvalidLeaseEnd,
+		/// 同一个区块结束的租约太多
+		TooManyLeases,
+		/// 没有提供给这个账户的出租条件
+		LeaseOfferNotExist,
+		/// 租金超过了愿意支付的上限
+		LeaseFeeTooHigh,
+		/// 小猫没有出租
+		LeaseNotExist,
+		/// 小猫被其他模块锁定
+		KittyLocked,
+		/// 小猫没有被锁定
+		KittyNotLocked,
     }
 }
 
@@ -93,6 +425,7 @@ decl_event!(
     pub enum Event<T>
     where
         <T as frame_system::Trait>::AccountId,
+        <T as frame_system::Trait>::BlockNumber,
 		<T as Trait>::KittyIndex,
 		Balance = BalanceOf<T>,
 	{
@@ -105,11 +438,87 @@ decl_event!(
 		/// 小猫的拥有权转移
 		Transferred(AccountId, AccountId, KittyIndex),
 
-		/// 一只小猫被挂单
-		Ask(AccountId, KittyIndex, Option<Balance>),
+		/// 一只小猫被挂单. None 表示下架
+		Ask(AccountId, KittyIndex, Option<Listing<Balance>>),
+
+		/// 一只小猫被出售 (卖家, 买家, 小猫, 成交价, 市场手续费, 创作者版税).
+		/// 卖家得到成交价减去手续费和版税.
+		Sold(AccountId, AccountId, KittyIndex, Balance, Balance, Balance),
+
+		/// 卖家为小猫创建了拍卖 (卖家, 小猫, 起拍价, 结束区块)
+		AuctionCreated(AccountId, KittyIndex, Balance, BlockNumber),
+
+		/// 出价 (出价人, 小猫, 金额)
+		Bid(AccountId, KittyIndex, Balance),
+
+		/// 之前的最高出价被超过, 出价金额已解除质押 (出价人, 小猫, 金额)
+		Outbid(AccountId, KittyIndex, Balance),
+
+		/// 拍卖结束 (小猫, 卖家, 买家, 成交价). 没有成交时买家为 None
+		AuctionSettled(KittyIndex, AccountId, Option<AccountId>, Balance),
 
-		/// 一只小猫被出售
-		Sold(AccountId, AccountId, KittyIndex, Balance),
+		/// 卖家取消了拍卖
+		AuctionCancelled(AccountId, KittyIndex),
+
+		/// 对小猫报价 (报价人, 小猫, 金额, 过期区块)
+		OfferMade(AccountId, KittyIndex, Balance, BlockNumber),
+
+		/// 拥有者接受了报价 (拥有者, 报价人, 小猫, 金额)
+		OfferAccepted(AccountId, AccountId, KittyIndex, Balance),
+
+		/// 报价人撤回了报价, 金额已解除质押 (报价人, 小猫, 金额)
+		OfferWithdrawn(AccountId, KittyIndex, Balance),
+
+		/// 报价过期, 金额已解除质押 (报价人, 小猫, 金额)
+		OfferExpired(AccountId, KittyIndex, Balance),
+
+		/// 繁殖时发生突变, 小猫表达出了其他小猫都没有的性状 (拥有者, 小猫, 稀有度评分)
+		RareMutation(AccountId, KittyIndex, u32),
+
+		/// 拥有者销毁了小猫, 押金已解除锁定
+		Burned(AccountId, KittyIndex),
+
+		/// 小猫被命名 (拥有者, 小猫, 名字)
+		NameSet(AccountId, KittyIndex, Vec<u8>),
+
+		/// 小猫的名字被清除, 押金已退还给起名的账户
+		NameCleared(AccountId, KittyIndex),
+
+		/// 拥有者授权或者取消授权一个账户处理这只小猫 (拥有者, 被授权的账户, 小猫)
+		Approval(AccountId, Option<AccountId>, KittyIndex),
+
+		/// 拥有者授权或者取消授权一个代理处理它所有的小猫 (拥有者, 代理, 是否授权)
+		ApprovalForAll(AccountId, AccountId, bool),
+
+		/// 拥有者把小猫挂出作为种猫 (拥有者, 小猫, 配种费, 是否只限白名单)
+		SireListed(AccountId, KittyIndex, Balance, bool),
+
+		/// 拥有者取消了种猫挂单
+		SireUnlisted(AccountId, KittyIndex),
+
+		/// 用别人的种猫繁殖出了小猫, 配种费已支付给种猫的拥有者 (繁殖者, 新小猫, 种猫, 配种费)
+		BredWithSire(AccountId, KittyIndex, KittyIndex, Balance),
+
+		/// 拥有者提出出租小猫 (拥有者, 承租人, 小猫, 结束区块, 租金)
+		LeaseOffered(AccountId, AccountId, KittyIndex, BlockNumber, Balance),
+
+		/// 拥有者撤回了还没有被接受的出租条件
+		LeaseOfferCancelled(AccountId, KittyIndex),
+
+		/// 承租人接受了出租条件, 租金已质押 (拥有者, 承租人, 小猫, 结束区块, 租金)
+		LeaseStarted(AccountId, AccountId, KittyIndex, BlockNumber, Balance),
+
+		/// 租约到期, 租金已支付给拥有者 (拥有者, 承租人, 小猫, 租金)
+		LeaseEnded(AccountId, AccountId, KittyIndex, Balance),
+
+		/// 拥有者提前终止了租约 (拥有者, 承租人, 小猫, 支付给拥有者的租金, 退还给承租人的租金)
+		LeaseTerminated(AccountId, AccountId, KittyIndex, Balance, Balance),
+
+		/// 小猫被其他模块锁定, 拥有者不变 (拥有者, 小猫)
+		Locked(AccountId, KittyIndex),
+
+		/// 小猫被解除锁定 (拥有者, 小猫)
+		Unlocked(AccountId, KittyIndex),
     }
 );
 
@@ -120,20 +529,105 @@ decl_module! {
 
         fn deposit_event() = default;
 
+        /// 繁殖双方的血缘距离下限
+        const MinAncestryDistance: u32 = T::MinAncestryDistance::get();
+
+        /// 第 0 代小猫繁殖之后的冷却区块数
+        const BreedingCooldown: T::BlockNumber = T::BreedingCooldown::get();
+
+        /// 每拥有一只小猫需要锁定的押金
+        const KittyDeposit: BalanceOf<T> = T::KittyDeposit::get();
+
+        /// 同一个区块最多结束的拍卖数量
+        const MaxAuctionsPerBlock: u32 = T::MaxAuctionsPerBlock::get();
+
+        /// 同一个区块最多过期的报价数量
+        const MaxOffersPerBlock: u32 = T::MaxOffersPerBlock::get();
+
+        /// `buy` 成交价中支付给市场的手续费比例
+        const MarketplaceFee: Perbill = T::MarketplaceFee::get();
+
+        /// 新小猫的创作者版税比例
+        const CreatorRoyalty: Perbill = T::CreatorRoyalty::get();
+
+        /// 繁殖时每对等位基因发生突变的概率
+        const MutationRate: Perbill = T::MutationRate::get();
+
+        /// 小猫名字的最大字节数
+        const MaxNameLength: u32 = T::MaxNameLength::get();
+
+        /// 给小猫起名需要质押的押金
+        const NameDeposit: BalanceOf<T> = T::NameDeposit::get();
+
+        /// 种猫白名单的最大账户数
+        const MaxSireWhitelist: u32 = T::MaxSireWhitelist::get();
+
+        /// 同一个区块最多结束的租约数量
+        const MaxLeasesPerBlock: u32 = T::MaxLeasesPerBlock::get();
+
+        /// 解除过期报价的质押, 结束到期的租约, 继续清理旧版本遗留的锁
+        fn on_initialize(n: T::BlockNumber) -> Weight {
+            let expired = <OfferExpiries<T>>::take(n);
+            for (kitty_id, who) in expired.iter() {
+                if let Some(offer) = <Offers<T>>::take(kitty_id, who) {
+                    T::Currency::unreserve(who, offer.amount);
+                    Self::deposit_event(RawEvent::OfferExpired(who.clone(), *kitty_id, offer.amount));
+                }
+            }
+
+            let ending = <LeasesEnding<T>>::take(n);
+            for kitty_id in ending.iter() {
+                if let Some(lease) = <Leases<T>>::take(kitty_id) {
+                    Self::end_lease(*kitty_id, lease);
+                }
+            }
+
+            let swept = Self::sweep_legacy_locks(LEGACY_LOCK_SWEEP_LIMIT);
+
+            T::WeightInfo::on_initialize(<AuctionsEnding<T>>::decode_len(n).unwrap_or(0) as u32)
+                .saturating_add(T::WeightInfo::expire_offers(expired.len() as u32))
+                .saturating_add(T::WeightInfo::expire_leases(ending.len() as u32))
+                .saturating_add(T::WeightInfo::sweep_legacy_locks(swept))
+        }
+
+        /// 结算在这个区块结束的拍卖
+        fn on_finalize(n: T::BlockNumber) {
+            for kitty_id in <AuctionsEnding<T>>::take(n) {
+                if let Some(auction) = <Auctions<T>>::take(kitty_id) {
+                    Self::settle_auction(kitty_id, auction);
+                }
+            }
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            let mut weight = 0;
+            if StorageVersion::get() == Releases::V1_0_0 {
+                weight += Self::migrate_to_v2();
+            }
+            if StorageVersion::get() == Releases::V2_0_0 {
+                weight += Self::migrate_to_v3();
+            }
+            if StorageVersion::get() == Releases::V3_0_0 {
+                weight += Self::migrate_to_v4();
+            }
+            if StorageVersion::get() == Releases::V4_0_0 {
+                weight += Self::migrate_to_v5();
+            }
+            weight
+        }
+
         /// 创建一只小猫
-        #[weight = 0]
+        #[weight = T::WeightInfo::create()]
         pub fn create(origin) -> dispatch::DispatchResult {
             let sender = ensure_signed(origin)?;
 
             let kitty_index = Self::next_kitty_id()?;
 
-            // 检测余额
-            Self::check_balance(&sender)?;
-            // 质押一定数量token
-            Self::account_lock(&sender, kitty_index);
+            // 检测余额是否足够锁定押金
+            Self::ensure_can_hold(&sender, Zero::zero())?;
 
             let dna = Self::random_value(&sender);
-            let new_kitty = Kitty{ dna: dna };
+            let new_kitty = Kitty{ dna: dna, generation: 0 };
 
             Self::insert_kitty(&sender, kitty_index, new_kitty);
 
@@ -143,17 +637,15 @@ decl_module! {
         }
 
         /// 繁殖小猫
-		#[weight = 0]
+		#[weight = T::WeightInfo::breed(Module::<T>::max_ancestors(T::MinAncestryDistance::get()))]
 		pub fn breed(origin, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> dispatch::DispatchResult {
 			let sender = ensure_signed(origin)?;
 
-            // 检测余额
-            Self::check_balance(&sender)?;
+            // 检测余额是否足够锁定押金
+            Self::ensure_can_hold(&sender, Zero::zero())?;
 
             // 繁殖小猫
 			let new_kitty_index = Self::do_breed(&sender, kitty_id_1, kitty_id_2)?;
-            // 质押一定数量token
-            Self::account_lock(&sender, new_kitty_index);
 
 			Self::deposit_event(RawEvent::Breeded(sender, new_kitty_index));
 
@@ -161,63 +653,544 @@ decl_module! {
 		}
 
 		/// 将小猫转让给其他人
-		#[weight = 0]
+		#[weight = T::WeightInfo::transfer(T::MaxSireWhitelist::get())]
 		pub fn transfer(origin, to: T::AccountId, kitty_index: T::KittyIndex) -> dispatch::DispatchResult {
 			let sender = ensure_signed(origin)?;
 
-			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_index))), Error::<T>::RequireOwner);
+			// 被授权的账户可以代替拥有者转让
+			let owner = Self::ensure_approved_or_owner(&sender, kitty_index)?;
+
+			Self::transfer_kitty(owner, to, kitty_index)
+		}
+
+		/// 由拥有者本人或者被授权的账户把小猫从 `from` 转让给 `to`
+		#[weight = T::WeightInfo::transfer_from(T::MaxSireWhitelist::get())]
+		pub fn transfer_from(origin, from: T::AccountId, to: T::AccountId, kitty_id: T::KittyIndex) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let owner = Self::ensure_approved_or_owner(&sender, kitty_id)?;
+			ensure!(owner == from, Error::<T>::WrongOwner);
+
+			Self::transfer_kitty(from, to, kitty_id)
+		}
+
+		/// 授权 `spender` 转让或者出售这只小猫, None 表示取消授权. 每只小猫同时只有一个被授权的账户.
+		/// 拥有者的代理也可以修改授权.
+		#[weight = T::WeightInfo::approve()]
+		pub fn approve(origin, kitty_id: T::KittyIndex, spender: Option<T::AccountId>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::RequireOwner)?;
+			ensure!(sender == owner || Self::is_approved_for_all(&owner, &sender), Error::<T>::RequireOwner);
+			ensure!(spender.as_ref() != Some(&owner), Error::<T>::ApproveToOwner);
+
+			<KittyApprovals<T>>::mutate_exists(kitty_id, |approved| *approved = spender.clone());
+
+			Self::deposit_event(RawEvent::Approval(owner, spender, kitty_id));
+
+			Ok(())
+		}
+
+		/// 授权或者取消授权 `operator` 处理自己所有的小猫, 包括以后得到的小猫
+		#[weight = T::WeightInfo::set_approval_for_all()]
+		pub fn set_approval_for_all(origin, operator: T::AccountId, approved: bool) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
 
-            Self::check_balance(&sender)?;
+			ensure!(operator != sender, Error::<T>::ApproveToOwner);
 
-			Self::do_transfer(&sender, &to, kitty_index);
-			Self::transfer_lock(&sender, &to, kitty_index);
+			if approved {
+				<OperatorApprovals<T>>::insert(&sender, &operator, true);
+			} else {
+				<OperatorApprovals<T>>::remove(&sender, &operator);
+			}
 
-			Self::deposit_event(RawEvent::Transferred(sender, to, kitty_index));
+			Self::deposit_event(RawEvent::ApprovalForAll(sender, operator, approved));
 
 			Ok(())
 		}
 
         /// 为自己的小猫设置卖价
-        /// None 表示下架小猫
-		#[weight = 0]
+        /// None 表示下架小猫. 每次设置价格都会生成新的挂单 nonce
+		#[weight = T::WeightInfo::ask()]
  		pub fn ask(origin, kitty_id: T::KittyIndex, new_price: Option<BalanceOf<T>>) -> dispatch::DispatchResult {
 			let sender = ensure_signed(origin)?;
 
-			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			// 被授权的账户可以代替拥有者挂单, 成交的货款仍然支付给拥有者
+			let owner = Self::ensure_approved_or_owner(&sender, kitty_id)?;
+			ensure!(!<Auctions<T>>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(!<Leases<T>>::contains_key(kitty_id), Error::<T>::KittyLeased);
+			ensure!(!Self::kitty_locked(kitty_id), Error::<T>::KittyLocked);
 
-			<KittyPrices<T>>::mutate_exists(kitty_id, |price| *price = new_price);
+			let listing = new_price.map(|price| Listing { price, nonce: Self::take_listing_nonce() });
+			<KittyPrices<T>>::mutate_exists(kitty_id, |current| *current = listing);
 
-			Self::deposit_event(RawEvent::Ask(sender, kitty_id, new_price));
+			Self::deposit_event(RawEvent::Ask(owner, kitty_id, listing));
 
 			Ok(())
 		}
 
 		/// 买一只小猫
-		#[weight = 0]
-		pub fn buy(origin, kitty_id: T::KittyIndex, price: BalanceOf<T>) -> dispatch::DispatchResult {
+		///
+		/// `price` 是愿意支付的最高价格, `nonce` 必须和看到的挂单一致.
+		/// 成交价按 `MarketplaceFee` 支付手续费, 按小猫记录的版税比例支付给创作者, 其余支付给卖家.
+		#[weight = T::WeightInfo::buy(T::MaxSireWhitelist::get())]
+		#[transactional]
+		pub fn buy(origin, kitty_id: T::KittyIndex, price: BalanceOf<T>, nonce: u64) -> dispatch::DispatchResult {
 			let sender = ensure_signed(origin)?;
 
 			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(owner != sender, Error::<T>::BuyOwnKitty);
+			ensure!(!Self::kitty_locked(kitty_id), Error::<T>::KittyLocked);
 
-			let kitty_price = Self::kitty_price(kitty_id).ok_or(Error::<T>::NotForSale)?;
+			let listing = Self::kitty_price(kitty_id).ok_or(Error::<T>::NotForSale)?;
+			ensure!(listing.nonce == nonce, Error::<T>::ListingChanged);
 
+			let kitty_price = listing.price;
 			ensure!(price >= kitty_price, Error::<T>::PriceTooLow);
 
-			T::Currency::transfer(&sender, &owner, kitty_price, ExistenceRequirement::KeepAlive)?;
+			// 付款之后还需要足够锁定这只小猫的押金
+			Self::ensure_can_hold(&sender, kitty_price)?;
 
-			<KittyPrices<T>>::remove(kitty_id);
+			let (fee, royalty) = Self::pay_for_kitty(&sender, &owner, kitty_id, kitty_price, false)?;
 
 			Self::do_transfer(&owner, &sender, kitty_id);
 
-			Self::deposit_event(RawEvent::Sold(owner, sender, kitty_id, kitty_price));
+			Self::deposit_event(RawEvent::Sold(owner, sender, kitty_id, kitty_price, fee, royalty));
+
+			Ok(())
+		}
+
+		/// 拍卖自己的小猫, 拍卖在 `end_block` 结束时结算
+		///
+		/// 小猫原来的挂单和种猫挂单会被取消, 拍卖期间小猫不能转让或者挂单.
+		#[weight = T::WeightInfo::create_auction(T::MaxSireWhitelist::get())]
+		pub fn create_auction(
+			origin,
+			kitty_id: T::KittyIndex,
+			kind: AuctionKind,
+			start_price: BalanceOf<T>,
+			reserve: BalanceOf<T>,
+			end_block: T::BlockNumber,
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			ensure!(!<Auctions<T>>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(!<Leases<T>>::contains_key(kitty_id), Error::<T>::KittyLeased);
+			ensure!(!Self::kitty_locked(kitty_id), Error::<T>::KittyLocked);
+
+			let now = <frame_system::Module<T>>::block_number();
+			ensure!(end_block > now, Error::<T>::InvalidAuctionEnd);
+			if kind == AuctionKind::Dutch {
+				ensure!(reserve <= start_price, Error::<T>::InvalidReservePrice);
+			}
+
+			let ending = Self::auctions_ending(end_block);
+			ensure!((ending.len() as u32) < T::MaxAuctionsPerBlock::get(), Error::<T>::TooManyAuctions);
+			<AuctionsEnding<T>>::append(end_block, kitty_id);
+
+			<KittyPrices<T>>::remove(kitty_id);
+			Self::remove_sire_listing(kitty_id);
+			<Auctions<T>>::insert(kitty_id, Auction {
+				seller: sender.clone(),
+				kind,
+				start_price,
+				reserve,
+				start_block: now,
+				end_block,
+				best_bid: None,
+			});
+
+			Self::deposit_event(RawEvent::AuctionCreated(sender, kitty_id, start_price, end_block));
+
+			Ok(())
+		}
+
+		/// 为拍卖中的小猫出价
+		///
+		/// 英式拍卖的出价会被质押, 直到被更高的出价超过或者拍卖结算.
+		/// 荷兰式拍卖以当前价格立即成交, `amount` 是愿意支付的最高价格.
+		/// 成交价和 `buy` 一样支付手续费和版税.
+		#[weight = T::WeightInfo::bid()]
+		pub fn bid(origin, kitty_id: T::KittyIndex, amount: BalanceOf<T>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let mut auction = Self::auction(kitty_id).ok_or(Error::<T>::AuctionNotExist)?;
+			ensure!(auction.seller != sender, Error::<T>::BidOnOwnKitty);
+
+			match auction.kind {
+				AuctionKind::English => {
+					ensure!(amount >= auction.start_price, Error::<T>::BidTooLow);
+					if let Some((_, best)) = &auction.best_bid {
+						ensure!(amount > *best, Error::<T>::BidTooLow);
+					}
+
+					// 出价之后还需要足够锁定这只小猫的押金
+					Self::ensure_can_hold(&sender, amount)?;
+					T::Currency::reserve(&sender, amount)?;
+
+					if let Some((bidder, best)) = auction.best_bid.take() {
+						T::Currency::unreserve(&bidder, best);
+						Self::deposit_event(RawEvent::Outbid(bidder, kitty_id, best));
+					}
+
+					auction.best_bid = Some((sender.clone(), amount));
+					<Auctions<T>>::insert(kitty_id, auction);
+
+					Self::deposit_event(RawEvent::Bid(sender, kitty_id, amount));
+				}
+				AuctionKind::Dutch => {
+					let now = <frame_system::Module<T>>::block_number();
+					let price = Self::dutch_price(&auction, now);
+					ensure!(amount >= price, Error::<T>::BidTooLow);
+
+					// 付款之后还需要足够锁定这只小猫的押金
+					Self::ensure_can_hold(&sender, price)?;
+					Self::pay_for_kitty(&sender, &auction.seller, kitty_id, price, false)?;
+
+					<Auctions<T>>::remove(kitty_id);
+					<AuctionsEnding<T>>::mutate(auction.end_block, |ending| ending.retain(|id| *id != kitty_id));
+					Self::do_transfer(&auction.seller, &sender, kitty_id);
+
+					Self::deposit_event(RawEvent::Bid(sender.clone(), kitty_id, price));
+					Self::deposit_event(RawEvent::AuctionSettled(kitty_id, auction.seller, Some(sender), price));
+				}
+			}
+
+			Ok(())
+		}
+
+		/// 对一只小猫报价, 报价金额会被质押直到被接受、撤回或者在 `expiry` 过期
+		#[weight = T::WeightInfo::make_offer()]
+		pub fn make_offer(
+			origin,
+			kitty_id: T::KittyIndex,
+			amount: BalanceOf<T>,
+			expiry: T::BlockNumber,
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(owner != sender, Error::<T>::OfferOnOwnKitty);
+			ensure!(!<Offers<T>>::contains_key(kitty_id, &sender), Error::<T>::OfferAlreadyExist);
+			ensure!(expiry > <frame_system::Module<T>>::block_number(), Error::<T>::InvalidOfferExpiry);
+
+			let expiring = Self::offer_expiries(expiry);
+			ensure!((expiring.len() as u32) < T::MaxOffersPerBlock::get(), Error::<T>::TooManyOffers);
+
+			// 报价之后还需要足够锁定这只小猫的押金
+			Self::ensure_can_hold(&sender, amount)?;
+			T::Currency::reserve(&sender, amount)?;
+
+			<OfferExpiries<T>>::append(expiry, (kitty_id, sender.clone()));
+			<Offers<T>>::insert(kitty_id, &sender, Offer { amount, expiry });
+
+			Self::deposit_event(RawEvent::OfferMade(sender, kitty_id, amount, expiry));
+
+			Ok(())
+		}
+
+		/// 接受对自己小猫的报价, 小猫转给报价人
+		///
+		/// 质押的金额和 `buy` 一样支付手续费和版税, 其余支付给拥有者.
+		#[weight = T::WeightInfo::accept_offer(T::MaxSireWhitelist::get())]
+		pub fn accept_offer(origin, kitty_id: T::KittyIndex, buyer: T::AccountId) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			ensure!(!<Auctions<T>>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(!<Leases<T>>::contains_key(kitty_id), Error::<T>::KittyLeased);
+			ensure!(!Self::kitty_locked(kitty_id), Error::<T>::KittyLocked);
+
+			let offer = Self::take_offer(kitty_id, &buyer).ok_or(Error::<T>::OfferNotExist)?;
+
+			Self::pay_for_kitty(&buyer, &sender, kitty_id, offer.amount, true)?;
+
+			Self::do_transfer(&sender, &buyer, kitty_id);
+
+			Self::deposit_event(RawEvent::OfferAccepted(sender, buyer, kitty_id, offer.amount));
+
+			Ok(())
+		}
+
+		/// 撤回自己的报价, 解除质押
+		#[weight = T::WeightInfo::withdraw_offer()]
+		pub fn withdraw_offer(origin, kitty_id: T::KittyIndex) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let offer = Self::take_offer(kitty_id, &sender).ok_or(Error::<T>::OfferNotExist)?;
+			T::Currency::unreserve(&sender, offer.amount);
+
+			Self::deposit_event(RawEvent::OfferWithdrawn(sender, kitty_id, offer.amount));
+
+			Ok(())
+		}
+
+		/// 取消还没有人出价的拍卖
+		#[weight = T::WeightInfo::cancel_auction()]
+		pub fn cancel_auction(origin, kitty_id: T::KittyIndex) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let auction = Self::auction(kitty_id).ok_or(Error::<T>::AuctionNotExist)?;
+			ensure!(auction.seller == sender, Error::<T>::RequireOwner);
+			ensure!(auction.best_bid.is_none(), Error::<T>::AuctionHasBids);
+
+			<Auctions<T>>::remove(kitty_id);
+			<AuctionsEnding<T>>::mutate(auction.end_block, |ending| ending.retain(|id| *id != kitty_id));
+
+			Self::deposit_event(RawEvent::AuctionCancelled(sender, kitty_id));
+
+			Ok(())
+		}
+
+		/// 销毁自己的小猫, 解除它的押金和名字押金.
+		/// 小猫的血统记录会保留; 对它的报价不会立即退还, 到期或者撤回时解除质押.
+		#[weight = T::WeightInfo::burn(T::MaxSireWhitelist::get())]
+		pub fn burn(origin, kitty_id: T::KittyIndex) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			ensure!(!<Auctions<T>>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(!<Leases<T>>::contains_key(kitty_id), Error::<T>::KittyLeased);
+			ensure!(!Self::kitty_locked(kitty_id), Error::<T>::KittyLocked);
+
+			let kitty = <Kitties<T>>::take(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			Self::uncount_traits(&kitty.dna);
+
+			<KittyPrices<T>>::remove(kitty_id);
+			<KittyApprovals<T>>::remove(kitty_id);
+			<LeaseOffers<T>>::remove(kitty_id);
+			Self::remove_sire_listing(kitty_id);
+			<KittyOwners<T>>::remove(kitty_id);
+			<KittyCreators<T>>::remove(kitty_id);
+			<NextBreeding<T>>::remove(kitty_id);
+			Self::remove_owned_kitty(&sender, kitty_id);
+
+			if let Some((_, depositor, deposit)) = <KittyNames<T>>::take(kitty_id) {
+				T::Currency::unreserve(&depositor, deposit);
+			}
+
+			Self::deposit_event(RawEvent::Burned(sender, kitty_id));
+
+			Ok(())
+		}
+
+		/// 给自己的小猫起名, 空名字表示清除名字.
+		/// 起名需要质押 `NameDeposit`, 改名、清除名字或者小猫转移时退还给上一次起名的账户.
+		#[weight = T::WeightInfo::set_name()]
+		pub fn set_name(origin, kitty_id: T::KittyIndex, name: Vec<u8>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			ensure!(name.len() <= T::MaxNameLength::get() as usize, Error::<T>::NameTooLong);
+
+			if name.is_empty() {
+				let (_, depositor, deposit) = <KittyNames<T>>::take(kitty_id).ok_or(Error::<T>::NameNotSet)?;
+				T::Currency::unreserve(&depositor, deposit);
+
+				Self::deposit_event(RawEvent::NameCleared(sender, kitty_id));
+				return Ok(());
+			}
+
+			let deposit = T::NameDeposit::get();
+			match <KittyNames<T>>::get(kitty_id) {
+				// 同一个账户改名, 沿用已经质押的押金
+				Some((_, depositor, old_deposit)) if depositor == sender => {
+					if deposit > old_deposit {
+						T::Currency::reserve(&sender, deposit - old_deposit)
+							.map_err(|_| Error::<T>::BalanceNotEnough)?;
+					} else {
+						T::Currency::unreserve(&sender, old_deposit - deposit);
+					}
+				}
+				previous => {
+					T::Currency::reserve(&sender, deposit).map_err(|_| Error::<T>::BalanceNotEnough)?;
+					if let Some((_, depositor, old_deposit)) = previous {
+						T::Currency::unreserve(&depositor, old_deposit);
+					}
+				}
+			}
+
+			<KittyNames<T>>::insert(kitty_id, (name.clone(), sender.clone(), deposit));
+
+			Self::deposit_event(RawEvent::NameSet(sender, kitty_id, name));
+
+			Ok(())
+		}
+
+		/// 把自己的小猫挂出作为种猫, 其他账户支付 `fee` 之后可以用它繁殖.
+		/// `whitelist` 不为 None 时只有其中的账户可以使用. 重新挂出会替换原来的配种费和白名单.
+		#[weight = T::WeightInfo::list_sire(T::MaxSireWhitelist::get())]
+		pub fn list_sire(
+			origin,
+			kitty_id: T::KittyIndex,
+			fee: BalanceOf<T>,
+			whitelist: Option<Vec<T::AccountId>>,
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			ensure!(!<Auctions<T>>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(!<Leases<T>>::contains_key(kitty_id), Error::<T>::KittyLeased);
+			if let Some(whitelist) = &whitelist {
+				ensure!(whitelist.len() <= T::MaxSireWhitelist::get() as usize, Error::<T>::TooManySireApprovals);
+			}
+
+			<SireApprovals<T>>::remove_prefix(kitty_id);
+			for who in whitelist.iter().flatten() {
+				<SireApprovals<T>>::insert(kitty_id, who, true);
+			}
+
+			let restricted = whitelist.is_some();
+			<SireListings<T>>::insert(kitty_id, SireListing { fee, restricted });
+
+			Self::deposit_event(RawEvent::SireListed(sender, kitty_id, fee, restricted));
+
+			Ok(())
+		}
+
+		/// 取消自己小猫的种猫挂单
+		#[weight = T::WeightInfo::unlist_sire(T::MaxSireWhitelist::get())]
+		pub fn unlist_sire(origin, kitty_id: T::KittyIndex) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			ensure!(<SireListings<T>>::contains_key(kitty_id), Error::<T>::NotSireListed);
+
+			Self::remove_sire_listing(kitty_id);
+
+			Self::deposit_event(RawEvent::SireUnlisted(sender, kitty_id));
 
 			Ok(())
 		}
-    }
-}
 
-fn combine_dna(dna1: u8, dna2: u8, selector: u8) -> u8 {
-    (selector & dna1) | (!selector & dna2)
+		/// 用自己 (或者租来) 的小猫和别人挂出的种猫繁殖, 新小猫属于调用者
+		///
+		/// 配种费支付给种猫的拥有者, `max_fee` 是愿意支付的最高配种费.
+		/// 和 `breed` 一样检查血缘和冷却, 两只小猫都进入繁殖冷却.
+		#[weight = T::WeightInfo::breed_with_sire(Module::<T>::max_ancestors(T::MinAncestryDistance::get()))]
+		#[transactional]
+		pub fn breed_with_sire(
+			origin,
+			kitty_id: T::KittyIndex,
+			sire_id: T::KittyIndex,
+			max_fee: BalanceOf<T>,
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let kitty = Self::kitties(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			let sire = Self::kitties(sire_id).ok_or(Error::<T>::InvalidKittyId)?;
+			Self::ensure_user(&sender, kitty_id)?;
+
+			let listing = Self::sire_listing(sire_id).ok_or(Error::<T>::NotSireListed)?;
+			ensure!(listing.fee <= max_fee, Error::<T>::SireFeeTooHigh);
+			ensure!(!listing.restricted || Self::is_sire_approved(sire_id, &sender), Error::<T>::NotOnSireWhitelist);
+
+			// 付款之后还需要足够锁定新小猫的押金
+			Self::ensure_can_hold(&sender, listing.fee)?;
+
+			let sire_owner = Self::kitty_owner(sire_id).ok_or(Error::<T>::InvalidKittyId)?;
+			T::Currency::transfer(&sender, &sire_owner, listing.fee, ExistenceRequirement::KeepAlive)?;
+
+			let new_kitty_index = Self::breed_kitties(&sender, (kitty_id, kitty), (sire_id, sire))?;
+
+			Self::deposit_event(RawEvent::BredWithSire(sender, new_kitty_index, sire_id, listing.fee));
+
+			Ok(())
+		}
+
+		/// 提出把自己的小猫出租给 `to`, 直到 `until_block`, 租金为 `fee`.
+		/// 承租人调用 `accept_lease` 之后租约生效; 再次调用会替换还没有被接受的条件.
+		#[weight = T::WeightInfo::lease()]
+		pub fn lease(
+			origin,
+			kitty_id: T::KittyIndex,
+			to: T::AccountId,
+			until_block: T::BlockNumber,
+			fee: BalanceOf<T>,
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+			ensure!(to != sender, Error::<T>::LeaseToOwner);
+			ensure!(!<Auctions<T>>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(!<Leases<T>>::contains_key(kitty_id), Error::<T>::KittyLeased);
+			ensure!(!Self::kitty_locked(kitty_id), Error::<T>::KittyLocked);
+			ensure!(until_block > <frame_system::Module<T>>::block_number(), Error::<T>::InvalidLeaseEnd);
+
+			<LeaseOffers<T>>::insert(kitty_id, LeaseTerms { lessee: to.clone(), fee, until: until_block });
+
+			Self::deposit_event(RawEvent::LeaseOffered(sender, to, kitty_id, until_block, fee));
+
+			Ok(())
+		}
+
+		/// 接受提供给自己的出租条件, 质押租金, `max_fee` 是愿意支付的最高租金.
+		///
+		/// 小猫的一口价挂单和种猫挂单会被取消, 租约期间拥有者不能转让、出售或者拍卖这只小猫.
+		/// 租约到期时租金支付给拥有者.
+		#[weight = T::WeightInfo::accept_lease(T::MaxSireWhitelist::get())]
+		pub fn accept_lease(origin, kitty_id: T::KittyIndex, max_fee: BalanceOf<T>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let terms = Self::lease_offer(kitty_id).ok_or(Error::<T>::LeaseOfferNotExist)?;
+			ensure!(terms.lessee == sender, Error::<T>::LeaseOfferNotExist);
+			ensure!(terms.fee <= max_fee, Error::<T>::LeaseFeeTooHigh);
+
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(!<Auctions<T>>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(!Self::kitty_locked(kitty_id), Error::<T>::KittyLocked);
+			let now = <frame_system::Module<T>>::block_number();
+			ensure!(terms.until > now, Error::<T>::InvalidLeaseEnd);
+
+			let ending = Self::leases_ending(terms.until);
+			ensure!((ending.len() as u32) < T::MaxLeasesPerBlock::get(), Error::<T>::TooManyLeases);
+
+			T::Currency::reserve(&sender, terms.fee).map_err(|_| Error::<T>::BalanceNotEnough)?;
+
+			<LeasesEnding<T>>::append(terms.until, kitty_id);
+			<LeaseOffers<T>>::remove(kitty_id);
+			<KittyPrices<T>>::remove(kitty_id);
+			Self::remove_sire_listing(kitty_id);
+			<Leases<T>>::insert(kitty_id, Lease {
+				lessee: sender.clone(),
+				fee: terms.fee,
+				start: now,
+				until: terms.until,
+			});
+
+			Self::deposit_event(RawEvent::LeaseStarted(owner, sender, kitty_id, terms.until, terms.fee));
+
+			Ok(())
+		}
+
+		/// 撤回还没有被接受的出租条件, 或者提前终止租约
+		///
+		/// 提前终止时租金按已经过去的区块比例支付给拥有者, 其余退还给承租人.
+		#[weight = T::WeightInfo::terminate_lease()]
+		pub fn terminate_lease(origin, kitty_id: T::KittyIndex) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id))), Error::<T>::RequireOwner);
+
+			if <LeaseOffers<T>>::take(kitty_id).is_some() {
+				Self::deposit_event(RawEvent::LeaseOfferCancelled(sender, kitty_id));
+				return Ok(());
+			}
+
+			let lease = <Leases<T>>::take(kitty_id).ok_or(Error::<T>::LeaseNotExist)?;
+			<LeasesEnding<T>>::mutate(lease.until, |ending| ending.retain(|id| *id != kitty_id));
+
+			let now = <frame_system::Module<T>>::block_number();
+			let (earned, refund) = Self::split_lease_fee(&lease, now);
+			let _ = T::Currency::repatriate_reserved(&lease.lessee, &sender, earned, BalanceStatus::Free);
+			T::Currency::unreserve(&lease.lessee, refund);
+
+			Self::deposit_event(RawEvent::LeaseTerminated(sender, lease.lessee, kitty_id, earned, refund));
+
+			Ok(())
+		}
+    }
 }
 
 impl<T: Trait> Module<T> {
@@ -241,71 +1214,421 @@ impl<T: Trait> Module<T> {
     fn insert_owned_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) {
         <OwnedKittiesList<T>>::append(owner, kitty_id);
         <KittyOwners<T>>::insert(kitty_id, owner);
+
+        Self::update_lock(owner);
+    }
+
+    fn remove_owned_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+        <OwnedKittiesList<T>>::remove(owner, kitty_id);
+
+        Self::update_lock(owner);
     }
 
     fn insert_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex, kitty: Kitty) {
         // 创建一只小猫并放入数据库
+        Self::count_traits(&kitty.dna);
         Kitties::<T>::insert(kitty_id, kitty);
         KittiesCount::<T>::put(kitty_id + 1.into());
+        KittyCreators::<T>::insert(kitty_id, (owner.clone(), T::CreatorRoyalty::get()));
 
         Self::insert_owned_kitty(owner, kitty_id);
     }
 
+    // 统计一只新小猫表达出的性状
+    fn count_traits(dna: &[u8; 16]) {
+        for (kind, value) in KittyTraits::from_dna(dna).expressed().iter() {
+            TraitCounts::mutate(kind, value, |count| *count = count.saturating_add(1));
+        }
+    }
+
+    // 销毁小猫时从性状统计中去掉
+    fn uncount_traits(dna: &[u8; 16]) {
+        for (kind, value) in KittyTraits::from_dna(dna).expressed().iter() {
+            TraitCounts::mutate(kind, value, |count| *count = count.saturating_sub(1));
+        }
+    }
+
     fn do_breed(sender: &T::AccountId, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> sp_std::result::Result<T::KittyIndex, DispatchError> {
         let kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
         let kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
 
-        ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id_1))), Error::<T>::RequireOwner);
-        ensure!(<OwnedKitties<T>>::contains_key((&sender, Some(kitty_id_2))), Error::<T>::RequireOwner);
+        Self::ensure_user(sender, kitty_id_1)?;
+        Self::ensure_user(sender, kitty_id_2)?;
+
+        Self::breed_kitties(sender, (kitty_id_1, kitty1), (kitty_id_2, kitty2))
+    }
+
+    // 繁殖两只小猫, 新小猫属于 `sender`. 调用者已经检查过使用两只小猫的权限
+    fn breed_kitties(
+        sender: &T::AccountId,
+        (kitty_id_1, kitty1): (T::KittyIndex, Kitty),
+        (kitty_id_2, kitty2): (T::KittyIndex, Kitty),
+    ) -> sp_std::result::Result<T::KittyIndex, DispatchError> {
         ensure!(kitty_id_1 != kitty_id_2, Error::<T>::RequireDifferentParent);
+        ensure!(!Self::is_related(kitty_id_1, kitty_id_2, T::MinAncestryDistance::get()), Error::<T>::TooCloselyRelated);
+
+        let now = <frame_system::Module<T>>::block_number();
+        ensure!(Self::next_breeding(kitty_id_1) <= now, Error::<T>::KittyOnCooldown);
+        ensure!(Self::next_breeding(kitty_id_2) <= now, Error::<T>::KittyOnCooldown);
 
         let kitty_index = Self::next_kitty_id()?;
-        let kitty1_dna = kitty1.dna;
-        let kitty2_dna = kitty2.dna;
 
         // 生成一个128位的随机数
         let selector = Self::random_value(&sender);
-        let mut new_dna = [0u8; 16];
 
-        // 通过一对小猫的dna生成新的dna
-        for i in 0..kitty1_dna.len() {
-            new_dna[i] = combine_dna(kitty1_dna[i], kitty2_dna[i], selector[i]);
-        }
+        // 通过一对小猫的dna生成新的dna, 每对等位基因从父母各继承一个
+        let mut new_dna = genes::combine_dna(&kitty1.dna, &kitty2.dna, &selector);
+
+        // 每对等位基因都有可能突变
+        let entropy = (b"kitties/mutation", selector).using_encoded(blake2_256);
+        let mutations = genes::mutate(&mut new_dna, T::MutationRate::get(), &entropy);
 
-        Self::insert_kitty(sender, kitty_index, Kitty{ dna: new_dna });
-        // 存储这只猫的父母
-        KittyParents::<T>::insert(kitty_index, (kitty_id_1, kitty_id_2));
+        let generation = kitty1.generation.max(kitty2.generation).saturating_add(1);
+        Self::insert_kitty(sender, kitty_index, Kitty{ dna: new_dna, generation });
+        Self::record_lineage(kitty_index, kitty_id_1, kitty_id_2);
 
-        // 存储猫的配偶列表
-        let mut partner_1 = <KittyPartners::<T>>::get(kitty_id_1);
-        partner_1.insert(partner_1.len(), kitty_id_2);
-        let mut partner_2 = <KittyPartners::<T>>::get(kitty_id_2);
-        partner_2.insert(partner_2.len(), kitty_id_1);
+        if mutations > 0 {
+            let unique = KittyTraits::from_dna(&new_dna).expressed().iter()
+                .any(|(kind, value)| TraitCounts::get(kind, value) == 1);
+            if unique {
+                let score = Self::rarity_of(kitty_index).unwrap_or_default();
+                Self::deposit_event(RawEvent::RareMutation(sender.clone(), kitty_index, score));
+            }
+        }
 
-        // 将这只猫存储在父母的孩子列表下
-        let mut children_1 = <KittyChildren::<T>>::get(kitty_id_1);
-        children_1.insert(children_1.len(), kitty_index.clone());
-        let mut children_2 = <KittyChildren::<T>>::get(kitty_id_2);
-        children_2.insert(children_2.len(), kitty_index.clone());
+        <NextBreeding<T>>::insert(kitty_id_1, now + Self::cooldown_of(kitty1.generation));
+        <NextBreeding<T>>::insert(kitty_id_2, now + Self::cooldown_of(kitty2.generation));
 
         Ok(kitty_index)
     }
 
+    /// 第 `generation` 代小猫繁殖之后的冷却区块数
+    pub fn cooldown_of(generation: u32) -> T::BlockNumber {
+        T::BreedingCooldown::get().saturating_mul(generation.saturating_add(1).into())
+    }
+
+    /// 两只小猫本身以及往上 `distance` 代的祖先是否有重合
+    pub fn is_related(kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex, distance: u32) -> bool {
+        let lineage = |kitty_id| {
+            let mut lineage: Vec<_> = Self::ancestors(kitty_id, distance).into_iter().flatten().collect();
+            lineage.push(kitty_id);
+            lineage
+        };
+        let lineage_1 = lineage(kitty_id_1);
+        lineage(kitty_id_2).iter().any(|kitty_id| lineage_1.contains(kitty_id))
+    }
+
+    /// 检查往上 `distance` 代的血缘时两只小猫最多有多少个祖先, 用于计算繁殖的权重
+    pub fn max_ancestors(distance: u32) -> u32 {
+        let distance = distance.min(MAX_ANCESTRY_DEPTH);
+        2 * ((1 << (distance + 1)) - 2)
+    }
+
+    // 记录小猫的父母, 并写入父母的孩子和配偶
+    fn record_lineage(kitty_id: T::KittyIndex, parent_1: T::KittyIndex, parent_2: T::KittyIndex) {
+        KittyParents::<T>::insert(kitty_id, (parent_1, parent_2));
+
+        <KittyChildren<T>>::insert(parent_1, kitty_id, ());
+        <KittyChildren<T>>::insert(parent_2, kitty_id, ());
+
+        <KittyPartners<T>>::mutate(parent_1, parent_2, |litters| *litters = litters.saturating_add(1));
+        <KittyPartners<T>>::mutate(parent_2, parent_1, |litters| *litters = litters.saturating_add(1));
+    }
+
+    /// 查询小猫的孩子, 按编号排序
+    pub fn kitty_children(kitty_id: T::KittyIndex) -> Vec<T::KittyIndex> {
+        let mut children: Vec<_> = <KittyChildren<T>>::iter_prefix(kitty_id).map(|(child, _)| child).collect();
+        children.sort();
+        children
+    }
+
+    /// 查询小猫的配偶, 按编号排序
+    pub fn kitty_partners(kitty_id: T::KittyIndex) -> Vec<T::KittyIndex> {
+        let mut partners: Vec<_> = <KittyPartners<T>>::iter_prefix(kitty_id).map(|(partner, _)| partner).collect();
+        partners.sort();
+        partners
+    }
+
+    /// 查询小猫的祖先, 最多 `MAX_ANCESTRY_DEPTH` 代
+    ///
+    /// 第 i 个元素是往上第 i + 1 代的祖先, 按编号排序并去重. 没有更早的祖先时提前结束.
+    pub fn ancestors(kitty_id: T::KittyIndex, generations: u32) -> Vec<Vec<T::KittyIndex>> {
+        let mut ancestry = Vec::new();
+        let mut current = sp_std::vec![kitty_id];
+
+        for _ in 0..generations.min(MAX_ANCESTRY_DEPTH) {
+            let mut parents = Vec::new();
+            for kitty in current.iter() {
+                if <KittyParents<T>>::contains_key(kitty) {
+                    let (parent_1, parent_2) = Self::kitty_parents(kitty);
+                    parents.push(parent_1);
+                    parents.push(parent_2);
+                }
+            }
+            if parents.is_empty() {
+                break;
+            }
+            parents.sort();
+            parents.dedup();
+            ancestry.push(parents.clone());
+            current = parents;
+        }
+
+        ancestry
+    }
+
+    fn migrate_to_v2() -> Weight {
+        let migrated = sp_std::cell::Cell::new(0 as Weight);
+
+        #[derive(Decode)]
+        struct OldKitty {
+            dna: [u8; 16],
+        }
+
+        Kitties::<T>::translate::<OldKitty, _>(|_, old| {
+            migrated.set(migrated.get() + 1);
+            Some(Kitty { dna: old.dna, generation: 0 })
+        });
+
+        // 孩子的编号总是大于父母, 按编号顺序计算代数时父母已经算好
+        let count = Self::kitties_count();
+        let mut kitty_id = T::KittyIndex::default();
+        let mut bred = 0 as Weight;
+        while kitty_id < count {
+            if <KittyParents<T>>::contains_key(kitty_id) {
+                let (parent_1, parent_2) = Self::kitty_parents(kitty_id);
+                let generation = Self::kitties(parent_1).map(|kitty| kitty.generation)
+                    .max(Self::kitties(parent_2).map(|kitty| kitty.generation))
+                    .map_or(0, |generation| generation.saturating_add(1));
+                Kitties::<T>::mutate(kitty_id, |kitty| {
+                    if let Some(kitty) = kitty {
+                        kitty.generation = generation;
+                    }
+                });
+                Self::record_lineage(kitty_id, parent_1, parent_2);
+                bred += 1;
+            }
+            kitty_id += 1.into();
+        }
+
+        StorageVersion::put(Releases::V2_0_0);
+
+        let migrated = migrated.get();
+        T::DbWeight::get().reads_writes(migrated + bred * 3 + 1, migrated + bred * 7 + 1)
+    }
+
+    // 转移小猫的所有权, 原拥有者的挂单随之取消
     fn do_transfer(from: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex) {
-        <OwnedKittiesList<T>>::remove(&from, kitty_id);
+        <KittyPrices<T>>::remove(kitty_id);
+        <KittyApprovals<T>>::remove(kitty_id);
+        <LeaseOffers<T>>::remove(kitty_id);
+        Self::remove_sire_listing(kitty_id);
+        // 名字不随小猫转移, 押金退还给起名的账户
+        if let Some((_, depositor, deposit)) = <KittyNames<T>>::take(kitty_id) {
+            T::Currency::unreserve(&depositor, deposit);
+        }
+        Self::remove_owned_kitty(&from, kitty_id);
         Self::insert_owned_kitty(&to, kitty_id);
     }
 
-    // 检查余额
-    fn check_balance(sender: &T::AccountId) -> sp_std::result::Result<(), DispatchError> {
-        if T::Currency::free_balance(sender) < LOCK_AMOUNT.into() {
-            return Err(Error::<T>::BalanceNotEnough.into());
+    /// 小猫的使用者: 出租期间是承租人, 否则是拥有者
+    pub fn kitty_user(kitty_id: T::KittyIndex) -> Option<T::AccountId> {
+        Self::active_lease(kitty_id).map(|lease| lease.lessee).or_else(|| Self::kitty_owner(kitty_id))
+    }
+
+    // 检查 `who` 是小猫的使用者. 出租期间拥有者不能用它繁殖
+    fn ensure_user(who: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+        match Self::active_lease(kitty_id) {
+            Some(lease) => ensure!(lease.lessee == *who, Error::<T>::KittyLeased),
+            None => ensure!(<OwnedKitties<T>>::contains_key((who, Some(kitty_id))), Error::<T>::RequireOwner),
         }
         Ok(())
     }
 
-    // 生成lock_id
-    fn gen_lock_id(account: &T::AccountId, index: T::KittyIndex) -> [u8; 8] {
+    // 租约到期, 把质押的租金全部支付给拥有者
+    fn end_lease(kitty_id: T::KittyIndex, lease: LeaseOf<T>) {
+        match Self::kitty_owner(kitty_id) {
+            Some(owner) => {
+                let _ = T::Currency::repatriate_reserved(&lease.lessee, &owner, lease.fee, BalanceStatus::Free);
+                Self::deposit_event(RawEvent::LeaseEnded(owner, lease.lessee, kitty_id, lease.fee));
+            }
+            None => {
+                T::Currency::unreserve(&lease.lessee, lease.fee);
+            }
+        }
+    }
+
+    /// 租约在 `now` 提前终止时, 租金中 (支付给拥有者, 退还给承租人) 的部分.
+    /// 按 `start` 到 `until` 之间已经过去的区块比例计算.
+    pub fn split_lease_fee(lease: &LeaseOf<T>, now: T::BlockNumber) -> (BalanceOf<T>, BalanceOf<T>) {
+        let elapsed = now.saturating_sub(lease.start);
+        let duration = lease.until.saturating_sub(lease.start);
+        let earned = if elapsed >= duration {
+            lease.fee
+        } else {
+            Perbill::from_rational_approximation(elapsed, duration) * lease.fee
+        };
+        (earned, lease.fee.saturating_sub(earned))
+    }
+
+    // 清除种猫挂单和它的白名单
+    fn remove_sire_listing(kitty_id: T::KittyIndex) {
+        if <SireListings<T>>::take(kitty_id).is_some() {
+            <SireApprovals<T>>::remove_prefix(kitty_id);
+        }
+    }
+
+    /// `who` 是否可以处理这只小猫: 拥有者本人, 这只小猫被授权的账户, 或者拥有者的代理
+    pub fn is_approved_or_owner(who: &T::AccountId, kitty_id: T::KittyIndex) -> bool {
+        Self::ensure_approved_or_owner(who, kitty_id).is_ok()
+    }
+
+    // 检查 `who` 可以处理这只小猫, 返回小猫的拥有者
+    fn ensure_approved_or_owner(who: &T::AccountId, kitty_id: T::KittyIndex) -> sp_std::result::Result<T::AccountId, DispatchError> {
+        let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::RequireOwner)?;
+        ensure!(
+            owner == *who
+                || Self::kitty_approval(kitty_id).as_ref() == Some(who)
+                || Self::is_approved_for_all(&owner, who),
+            Error::<T>::RequireOwner
+        );
+        Ok(owner)
+    }
+
+    // 转让小猫的公共部分, 调用者已经检查过权限
+    fn transfer_kitty(from: T::AccountId, to: T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+        ensure!(!<Auctions<T>>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+        ensure!(!<Leases<T>>::contains_key(kitty_id), Error::<T>::KittyLeased);
+        ensure!(!Self::kitty_locked(kitty_id), Error::<T>::KittyLocked);
+
+        // 接收者需要锁定这只小猫的押金
+        Self::ensure_can_hold(&to, Zero::zero())?;
+
+        Self::do_transfer(&from, &to, kitty_id);
+
+        Self::deposit_event(RawEvent::Transferred(from, to, kitty_id));
+
+        Ok(())
+    }
+
+    // 分配一个新的挂单 nonce
+    fn take_listing_nonce() -> u64 {
+        let nonce = Self::next_listing_nonce();
+        NextListingNonce::put(nonce.wrapping_add(1));
+        nonce
+    }
+
+    /// 荷兰式拍卖在 `now` 的价格, 从起始价按区块线性下降到保留价
+    pub fn dutch_price(auction: &AuctionOf<T>, now: T::BlockNumber) -> BalanceOf<T> {
+        let duration = auction.end_block.saturating_sub(auction.start_block);
+        let elapsed = now.saturating_sub(auction.start_block).min(duration);
+        let decay = Perbill::from_rational_approximation(elapsed, duration)
+            * auction.start_price.saturating_sub(auction.reserve);
+        auction.start_price.saturating_sub(decay)
+    }
+
+    // 买家支付 `price`: 手续费交给 `T::Treasury`, 版税支付给创作者, 其余支付给卖家.
+    // 创作者账户已经不存在时, 版税也交给 `T::Treasury`. 返回 (手续费, 版税).
+    //
+    // `reserved` 为 true 时从买家质押的金额中支付 (报价和英式拍卖的出价), 否则从可用余额中支付.
+    fn pay_for_kitty(
+        buyer: &T::AccountId,
+        seller: &T::AccountId,
+        kitty_id: T::KittyIndex,
+        price: BalanceOf<T>,
+        reserved: bool,
+    ) -> sp_std::result::Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+        let payment = if reserved {
+            let (payment, _) = T::Currency::slash_reserved(buyer, price);
+            payment
+        } else {
+            T::Currency::withdraw(
+                buyer,
+                price,
+                WithdrawReasons::from(WithdrawReason::Transfer),
+                ExistenceRequirement::KeepAlive,
+            )?
+        };
+
+        let fee = T::MarketplaceFee::get() * price;
+        let (mut fee_payment, rest) = payment.split(fee);
+
+        let mut royalty = Zero::zero();
+        let mut seller_payment = rest;
+        if let Some((creator, rate)) = Self::kitty_creator(kitty_id) {
+            if &creator != seller {
+                royalty = (rate * price).min(seller_payment.peek());
+                let (royalty_payment, rest) = seller_payment.split(royalty);
+                seller_payment = rest;
+                if let Err(royalty_payment) = T::Currency::resolve_into_existing(&creator, royalty_payment) {
+                    fee_payment.subsume(royalty_payment);
+                }
+            }
+        }
+
+        T::Currency::resolve_creating(seller, seller_payment);
+        T::Treasury::on_unbalanced(fee_payment);
+
+        Ok((fee, royalty))
+    }
+
+    // 移除一个报价以及它的过期索引, 不处理质押
+    fn take_offer(kitty_id: T::KittyIndex, who: &T::AccountId) -> Option<OfferOf<T>> {
+        let offer = <Offers<T>>::take(kitty_id, who)?;
+        <OfferExpiries<T>>::mutate(offer.expiry, |expiring| {
+            expiring.retain(|(id, bidder)| !(*id == kitty_id && bidder == who))
+        });
+        Some(offer)
+    }
+
+    // 结算到期的拍卖. 英式拍卖的最高出价达到保留价时成交, 否则退还出价
+    fn settle_auction(kitty_id: T::KittyIndex, auction: AuctionOf<T>) {
+        match auction.best_bid {
+            Some((bidder, amount)) if amount >= auction.reserve => {
+                // 出价已经质押, 从质押中支付不会失败
+                let _ = Self::pay_for_kitty(&bidder, &auction.seller, kitty_id, amount, true);
+                Self::do_transfer(&auction.seller, &bidder, kitty_id);
+                Self::deposit_event(RawEvent::AuctionSettled(kitty_id, auction.seller, Some(bidder), amount));
+            }
+            best_bid => {
+                if let Some((bidder, amount)) = best_bid {
+                    T::Currency::unreserve(&bidder, amount);
+                }
+                Self::deposit_event(RawEvent::AuctionSettled(kitty_id, auction.seller, None, Zero::zero()));
+            }
+        }
+    }
+
+    /// 拥有 `count` 只小猫需要锁定的押金
+    pub fn deposit_for(count: u32) -> BalanceOf<T> {
+        T::KittyDeposit::get().saturating_mul(count.into())
+    }
+
+    // 检查账户在支付 `extra` 之后, 余额是否足够再多锁定一只小猫的押金
+    fn ensure_can_hold(who: &T::AccountId, extra: BalanceOf<T>) -> DispatchResult {
+        let required = Self::deposit_for(Self::owned_kitties_count(who).saturating_add(1))
+            .saturating_add(extra);
+        ensure!(T::Currency::free_balance(who) >= required, Error::<T>::BalanceNotEnough);
+        Ok(())
+    }
+
+    // 按照拥有的小猫数量更新账户的锁
+    fn update_lock(who: &T::AccountId) {
+        let count = Self::owned_kitties_count(who);
+        if count == 0 {
+            T::Currency::remove_lock(KITTIES_ID, who);
+        } else {
+            T::Currency::set_lock(
+                KITTIES_ID,
+                who,
+                Self::deposit_for(count),
+                WithdrawReasons::except(WithdrawReason::TransactionPayment),
+            );
+        }
+    }
+
+    // 旧版本每只小猫单独一个锁, 锁的 id 由锁所在的账户和小猫编号生成
+    fn legacy_lock_id(account: &T::AccountId, index: T::KittyIndex) -> LockIdentifier {
         let payload = (
             &account,
             index,
@@ -313,24 +1636,303 @@ impl<T: Trait> Module<T> {
         payload.using_encoded(twox_64)
     }
 
-    // 质押token
-    fn account_lock(sender: &T::AccountId, kitty_index: T::KittyIndex) {
-        let lock_id = Self::gen_lock_id(&sender, kitty_index);
-        T::Currency::set_lock(
-            lock_id,
-            &sender,
-            LOCK_AMOUNT.into(),
-            WithdrawReasons::except(WithdrawReason::TransactionPayment),
-        );
+    // 清理小猫现在的拥有者身上旧版本每只小猫一个的锁, 然后按照拥有的小猫数量为每个账户设置一个锁.
+    //
+    // 旧版本转让和出售小猫时没有解除原拥有者的锁, 这些锁没有记录可查,
+    // 只能逐个账户、逐只小猫解除. 迁移只设置清理的起点, 之后由 `on_initialize` 分多个区块完成.
+    fn migrate_to_v3() -> Weight {
+        let mut kitties = 0 as Weight;
+        let mut owners = BTreeSet::new();
+        for (kitty_id, owner) in <KittyOwners<T>>::iter() {
+            T::Currency::remove_lock(Self::legacy_lock_id(&owner, kitty_id), &owner);
+            <OwnedKittiesCount<T>>::mutate(&owner, |count| *count = count.saturating_add(1));
+            owners.insert(owner);
+            kitties += 1;
+        }
+
+        let accounts = owners.len() as Weight;
+        for owner in owners.iter() {
+            Self::update_lock(owner);
+        }
+
+        if kitties > 0 {
+            if let Some(first) = Self::next_account_key(&Self::account_prefix()) {
+                <LegacyLockSweep<T>>::put((first, T::KittyIndex::zero()));
+            }
+        }
+
+        StorageVersion::put(Releases::V3_0_0);
+
+        // 每只小猫读取拥有者, 读写锁和数量; 每个拥有者读取数量, 读写锁; 最后设置清理的起点
+        T::DbWeight::get().reads_writes(kitties * 3 + accounts * 2 + 2, kitties * 2 + accounts + 2)
+    }
+
+    // `frame_system::Account` 的存储键前缀
+    fn account_prefix() -> [u8; 32] {
+        <frame_system::Account<T> as frame_support::storage::StoragePrefixedMap<_>>::final_prefix()
+    }
+
+    // `frame_system::Account` 中排在 `key` 之后的下一个账户的存储键
+    fn next_account_key(key: &[u8]) -> Option<Vec<u8>> {
+        let prefix = Self::account_prefix();
+        sp_io::storage::next_key(key).filter(|next| next.starts_with(&prefix))
     }
 
-    // 转移质押的token
-    fn transfer_lock(from: &T::AccountId, to: &T::AccountId, kitty_index: T::KittyIndex) {
-        let old_lock_id = Self::gen_lock_id(to, kitty_index);
+    // 按 `LegacyLockSweep` 记录的进度解除旧版本遗留的锁, 最多走 `limit` 步, 返回实际的步数.
+    // 每个账户依次尝试解除编号小于 `KittiesCount` 的所有小猫的锁.
+    fn sweep_legacy_locks(limit: u32) -> u32 {
+        let (mut key, mut next_id) = match Self::legacy_lock_sweep() {
+            Some(cursor) => cursor,
+            None => return 0,
+        };
+        let count = Self::kitties_count();
+        let mut steps = 0;
+
+        loop {
+            // blake2_128_concat 的存储键以账户本身结尾
+            match key.get(48..).and_then(|mut raw| T::AccountId::decode(&mut raw).ok()) {
+                Some(account) => {
+                    while next_id < count && steps < limit {
+                        T::Currency::remove_lock(Self::legacy_lock_id(&account, next_id), &account);
+                        next_id += One::one();
+                        steps += 1;
+                    }
+                }
+                None => next_id = count,
+            }
+
+            if next_id < count {
+                <LegacyLockSweep<T>>::put((key, next_id));
+                return steps;
+            }
+
+            match Self::next_account_key(&key) {
+                Some(next) if steps < limit => {
+                    key = next;
+                    next_id = Zero::zero();
+                    steps += 1;
+                }
+                Some(next) => {
+                    <LegacyLockSweep<T>>::put((next, T::KittyIndex::zero()));
+                    return steps;
+                }
+                None => {
+                    <LegacyLockSweep<T>>::kill();
+                    return steps;
+                }
+            }
+        }
+    }
+
+    // 为已有的挂单分配 nonce
+    fn migrate_to_v4() -> Weight {
+        let migrated = sp_std::cell::Cell::new(0 as Weight);
+
+        <KittyPrices<T>>::translate::<BalanceOf<T>, _>(|_, price| {
+            migrated.set(migrated.get() + 1);
+            Some(Listing { price, nonce: Self::take_listing_nonce() })
+        });
 
-        T::Currency::remove_lock(old_lock_id, from);
+        StorageVersion::put(Releases::V4_0_0);
+
+        let migrated = migrated.get();
+        T::DbWeight::get().reads_writes(migrated * 2 + 1, migrated * 2 + 1)
+    }
+
+    // 统计已有小猫的性状
+    fn migrate_to_v5() -> Weight {
+        let mut kitties = 0 as Weight;
+        for (_, kitty) in <Kitties<T>>::iter() {
+            Self::count_traits(&kitty.dna);
+            kitties += 1;
+        }
+
+        StorageVersion::put(Releases::V5_0_0);
+
+        let pairs = genes::GENE_PAIRS as Weight;
+        T::DbWeight::get().reads_writes(kitties * (pairs + 1) + 1, kitties * pairs + 1)
+    }
+
+    /// 查询一只小猫的完整信息
+    pub fn kitty_info(kitty_id: T::KittyIndex) -> Option<KittyInfo<T::AccountId, T::KittyIndex, BalanceOf<T>>> {
+        let kitty = Self::kitties(kitty_id)?;
+        let owner = Self::kitty_owner(kitty_id)?;
+        let listing = Self::kitty_price(kitty_id);
+        let parents = if <KittyParents<T>>::contains_key(kitty_id) {
+            Some(Self::kitty_parents(kitty_id))
+        } else {
+            None
+        };
+
+        Some(KittyInfo {
+            id: kitty_id,
+            dna: kitty.dna,
+            generation: kitty.generation,
+            owner,
+            price: listing.map(|listing| listing.price),
+            listing_nonce: listing.map(|listing| listing.nonce),
+            parents,
+            children: Self::kitty_children(kitty_id),
+            name: Self::kitty_name(kitty_id).map(|(name, _, _)| name),
+            lessee: Self::active_lease(kitty_id).map(|lease| lease.lessee),
+        })
+    }
+
+    /// 分页查询某个账户拥有的小猫, 每页 `KITTIES_PAGE_SIZE` 只
+    pub fn kitties_of(owner: &T::AccountId, page: u32) -> Vec<KittyInfo<T::AccountId, T::KittyIndex, BalanceOf<T>>> {
+        let skip = page.saturating_mul(KITTIES_PAGE_SIZE) as usize;
+        <OwnedKittiesList<T>>::iter(owner)
+            .skip(skip)
+            .take(KITTIES_PAGE_SIZE as usize)
+            .filter_map(Self::kitty_info)
+            .collect()
+    }
+
+    /// 查询某个账户在 `after` 之后拥有的至多 `limit` 只小猫, None 表示从第一只开始.
+    /// 下一页从这一页的最后一只之后开始, 翻页期间小猫的增减不会导致重复或者遗漏.
+    pub fn kitties_of_after(
+        owner: &T::AccountId,
+        after: Option<T::KittyIndex>,
+        limit: u32,
+    ) -> Vec<KittyInfo<T::AccountId, T::KittyIndex, BalanceOf<T>>> {
+        <OwnedKittiesList<T>>::page(owner, after, limit.min(KITTIES_PAGE_SIZE))
+            .into_iter()
+            .filter_map(Self::kitty_info)
+            .collect()
+    }
 
-        Self::account_lock(to, kitty_index);
+    /// 某个账户拥有的所有小猫的编号
+    pub fn owned_kitty_ids(owner: &T::AccountId) -> Vec<T::KittyIndex> {
+        <OwnedKittiesList<T>>::iter(owner).collect()
+    }
+
+    /// 现存的小猫数量, 不包括已经销毁的小猫
+    pub fn population() -> u64 {
+        // 每只小猫在每种性状上都恰好计数一次
+        TraitCounts::iter_prefix_values(0).map(u64::from).sum()
+    }
+
+    /// 检查小猫相关存储的一致性, 返回发现的第一个问题:
+    ///
+    /// - `Kitties` 中的每只小猫编号小于 `KittiesCount`, 在 `KittyOwners` 中有且只有一个拥有者,
+    ///   并且在拥有者的 `OwnedKitties` 链表中
+    /// - 每个 `OwnedKitties` 链表的 prev 和 next 互相对应, 没有环, 长度等于 `OwnedKittiesCount`,
+    ///   链表中的小猫都属于这个账户, 没有不在任何链表中的节点
+    /// - 挂单、拍卖、授权和名字都指向存在的小猫, 拍卖中的小猫没有挂单
+    /// - `TraitCounts` 与现存的小猫数量一致
+    ///
+    /// 需要遍历全部存储, 只用于测试和离线审计.
+    pub fn do_try_state() -> Result<(), &'static str> {
+        let count = Self::kitties_count();
+        let mut kitties = 0u64;
+        for (kitty_id, _) in <Kitties<T>>::iter() {
+            ensure!(kitty_id < count, "kitty id is not below KittiesCount");
+            let owner = Self::kitty_owner(kitty_id).ok_or("kitty has no owner")?;
+            ensure!(<OwnedKittiesList<T>>::contains(&owner, kitty_id), "kitty is missing from its owner's list");
+            kitties += 1;
+        }
+
+        for (kitty_id, _) in <KittyOwners<T>>::iter() {
+            ensure!(<Kitties<T>>::contains_key(kitty_id), "owner recorded for a missing kitty");
+        }
+
+        // 逐个遍历链表, 记录每个账户走过的节点数
+        let mut linked = 0u64;
+        let mut heads = BTreeSet::new();
+        for ((owner, value), head) in <OwnedKitties<T>>::iter() {
+            if value.is_some() {
+                continue;
+            }
+            let mut seen = BTreeSet::new();
+            let mut prev = None;
+            let mut cursor = head.next;
+            while let Some(kitty_id) = cursor {
+                ensure!(seen.insert(kitty_id), "owned kitties list has a cycle");
+                let item = Self::owned_kitties((owner.clone(), Some(kitty_id))).ok_or("owned kitties list is broken")?;
+                ensure!(item.prev == prev, "owned kitties list prev does not match next");
+                ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(&owner), "listed kitty belongs to another account");
+                prev = Some(kitty_id);
+                cursor = item.next;
+            }
+            ensure!(head.prev == prev, "owned kitties list head does not point to the last kitty");
+            ensure!(Self::owned_kitties_count(&owner) as usize == seen.len(), "OwnedKittiesCount does not match the list");
+            linked += seen.len() as u64;
+            heads.insert(owner);
+        }
+
+        let nodes = <OwnedKitties<T>>::iter().filter(|((_, value), _)| value.is_some()).count() as u64;
+        ensure!(nodes == linked, "owned kitties list has unreachable nodes");
+        ensure!(linked == kitties, "number of listed kitties does not match Kitties");
+        for (owner, owned) in <OwnedKittiesCount<T>>::iter() {
+            ensure!(owned == 0 || heads.contains(&owner), "OwnedKittiesCount recorded without a list");
+        }
+
+        for (kitty_id, _) in <KittyPrices<T>>::iter() {
+            ensure!(<Kitties<T>>::contains_key(kitty_id), "price recorded for a missing kitty");
+            ensure!(!<Auctions<T>>::contains_key(kitty_id), "kitty is listed while in auction");
+        }
+        for (kitty_id, _) in <Auctions<T>>::iter() {
+            ensure!(<Kitties<T>>::contains_key(kitty_id), "auction for a missing kitty");
+        }
+        for (kitty_id, _) in <LockedKitties<T>>::iter() {
+            ensure!(<Kitties<T>>::contains_key(kitty_id), "lock recorded for a missing kitty");
+            ensure!(!<Auctions<T>>::contains_key(kitty_id), "kitty is locked while in auction");
+            ensure!(!<Leases<T>>::contains_key(kitty_id), "kitty is locked while leased");
+        }
+        for (kitty_id, _) in <KittyApprovals<T>>::iter() {
+            ensure!(<Kitties<T>>::contains_key(kitty_id), "approval recorded for a missing kitty");
+        }
+        for (kitty_id, _) in <KittyNames<T>>::iter() {
+            ensure!(<Kitties<T>>::contains_key(kitty_id), "name recorded for a missing kitty");
+        }
+        for (kitty_id, _) in <SireListings<T>>::iter() {
+            ensure!(<Kitties<T>>::contains_key(kitty_id), "sire listing for a missing kitty");
+            ensure!(!<Auctions<T>>::contains_key(kitty_id), "kitty is listed as a sire while in auction");
+        }
+        for (kitty_id, _, _) in <SireApprovals<T>>::iter() {
+            ensure!(
+                Self::sire_listing(kitty_id).map_or(false, |listing| listing.restricted),
+                "sire whitelist without a restricted listing"
+            );
+        }
+        for (kitty_id, _) in <LeaseOffers<T>>::iter() {
+            ensure!(<Kitties<T>>::contains_key(kitty_id), "lease offer for a missing kitty");
+            ensure!(!<Leases<T>>::contains_key(kitty_id), "lease offer for a leased kitty");
+        }
+        for (kitty_id, lease) in <Leases<T>>::iter() {
+            let owner = Self::kitty_owner(kitty_id).ok_or("lease for a missing kitty")?;
+            ensure!(owner != lease.lessee, "kitty is leased to its owner");
+            ensure!(Self::leases_ending(lease.until).contains(&kitty_id), "lease is not scheduled to end");
+            ensure!(!<KittyPrices<T>>::contains_key(kitty_id), "kitty is listed while leased");
+            ensure!(!<Auctions<T>>::contains_key(kitty_id), "kitty is in auction while leased");
+            ensure!(!<SireListings<T>>::contains_key(kitty_id), "kitty is listed as a sire while leased");
+        }
+
+        for kind in 0..genes::GENE_PAIRS as u8 {
+            let counted: u64 = TraitCounts::iter_prefix_values(kind).map(u64::from).sum();
+            ensure!(counted == kitties, "TraitCounts does not match Kitties");
+        }
+
+        Ok(())
+    }
+
+    /// 解读一只小猫的 DNA
+    pub fn kitty_traits(kitty_id: T::KittyIndex) -> Option<KittyTraits> {
+        Self::kitties(kitty_id).map(|kitty| KittyTraits::from_dna(&kitty.dna))
+    }
+
+    /// 小猫的稀有度评分, 性状越少见分数越高.
+    /// 每种性状计 `小猫总数 * 100 / 表达出同样取值的小猫数量` 分, 再把各种性状的分数相加,
+    /// 所以评分会随着小猫数量的增长而变化.
+    pub fn rarity_of(kitty_id: T::KittyIndex) -> Option<u32> {
+        let expressed = Self::kitty_traits(kitty_id)?.expressed();
+
+        let population = Self::population();
+        let score: u64 = expressed.iter()
+            .map(|(kind, value)| population * 100 / u64::from(TraitCounts::get(kind, value).max(1)))
+            .sum();
+
+        Some(score.min(u64::from(u32::max_value())) as u32)
     }
 
     // 把AccountId 转成 u8 数组
@@ -340,3 +1942,50 @@ impl<T: Trait> Module<T> {
         b
     }
 }
+
+impl<T: Trait> UniqueAssets<T::AccountId> for Module<T> {
+    type AssetId = T::KittyIndex;
+
+    fn owner_of(kitty_id: T::KittyIndex) -> Option<T::AccountId> {
+        Self::kitty_owner(kitty_id)
+    }
+
+    fn transfer(from: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+        ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(from), Error::<T>::WrongOwner);
+        Self::transfer_kitty(from.clone(), to.clone(), kitty_id)
+    }
+
+    fn total_supply() -> u64 {
+        Self::population()
+    }
+
+    fn assets_of(owner: &T::AccountId) -> Vec<T::KittyIndex> {
+        Self::owned_kitty_ids(owner)
+    }
+
+    fn lock(owner: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+        ensure!(Self::kitty_owner(kitty_id).as_ref() == Some(owner), Error::<T>::WrongOwner);
+        ensure!(!<Auctions<T>>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+        ensure!(!<Leases<T>>::contains_key(kitty_id), Error::<T>::KittyLeased);
+        ensure!(!Self::kitty_locked(kitty_id), Error::<T>::KittyLocked);
+
+        <LockedKitties<T>>::insert(kitty_id, true);
+        Self::deposit_event(RawEvent::Locked(owner.clone(), kitty_id));
+
+        Ok(())
+    }
+
+    fn unlock(kitty_id: T::KittyIndex) -> DispatchResult {
+        ensure!(Self::kitty_locked(kitty_id), Error::<T>::KittyNotLocked);
+        let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+
+        <LockedKitties<T>>::remove(kitty_id);
+        Self::deposit_event(RawEvent::Unlocked(owner, kitty_id));
+
+        Ok(())
+    }
+
+    fn is_locked(kitty_id: T::KittyIndex) -> bool {
+        Self::kitty_locked(kitty_id)
+    }
+}
@@ -1,6 +1,7 @@
 use frame_support::{ StorageMap, Parameter };
 use sp_runtime::traits::Member;
 use codec::{ Encode, Decode };
+use sp_std::prelude::*;
 
 #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
 #[derive(Encode, Decode)]
@@ -9,13 +10,16 @@ pub struct LinkedItem<Item> {
     pub next: Option<Item>,
 }
 
-pub struct LinkedList<Storage, Key, Item>(sp_std::marker::PhantomData<(Storage, Key, Item)>);
+/// 保存在 `Storage` 中的双向循环链表, `(key, None)` 是链表头, 它的 `next` 是第一项, `prev` 是最后一项.
+/// `Counter` 记录每个链表的长度.
+pub struct LinkedList<Storage, Counter, Key, Item>(sp_std::marker::PhantomData<(Storage, Counter, Key, Item)>);
 
-impl<Storage, Key, Value> LinkedList<Storage, Key, Value>
+impl<Storage, Counter, Key, Value> LinkedList<Storage, Counter, Key, Value>
 where
     Value: Parameter + Member + Copy,
     Key: Parameter,
     Storage: StorageMap<(Key, Option<Value>), LinkedItem<Value>, Query = Option<LinkedItem<Value>>>,
+    Counter: StorageMap<Key, u32, Query = u32>,
 {
     fn read_head(key: &Key) -> LinkedItem<Value> {
         Self::read(key, None)
@@ -58,6 +62,8 @@ where
             next: None,
         };
         Self::write(key, Some(value), item);
+
+        Counter::mutate(key, |len| *len = len.saturating_add(1));
     }
 
     pub fn remove(key: &Key, value: Value) {
@@ -78,6 +84,111 @@ where
             };
 
             Self::write(key, item.next, n_next);
+
+            Counter::mutate(key, |len| *len = len.saturating_sub(1));
+        }
+    }
+
+    /// 把 `value` 插入到 `anchor` 之后. `anchor` 不在链表中或者 `value` 已经在链表中时返回 false.
+    pub fn insert_after(key: &Key, anchor: Value, value: Value) -> bool {
+        if !Self::contains(key, anchor) {
+            return false;
+        }
+        let next = Self::read(key, Some(anchor)).next;
+        Self::insert_between(key, Some(anchor), next, value)
+    }
+
+    /// 把 `value` 插入到 `anchor` 之前. `anchor` 不在链表中或者 `value` 已经在链表中时返回 false.
+    pub fn insert_before(key: &Key, anchor: Value, value: Value) -> bool {
+        if !Self::contains(key, anchor) {
+            return false;
+        }
+        let prev = Self::read(key, Some(anchor)).prev;
+        Self::insert_between(key, prev, Some(anchor), value)
+    }
+
+    // 把 `value` 插入到相邻的 `prev` 和 `next` 之间, None 表示链表头
+    fn insert_between(key: &Key, prev: Option<Value>, next: Option<Value>, value: Value) -> bool {
+        if Self::contains(key, value) {
+            return false;
+        }
+
+        let prev_item = Self::read(key, prev);
+        Self::write(key, prev, LinkedItem { prev: prev_item.prev, next: Some(value) });
+
+        let next_item = Self::read(key, next);
+        Self::write(key, next, LinkedItem { prev: Some(value), next: next_item.next });
+
+        Self::write(key, Some(value), LinkedItem { prev, next });
+
+        Counter::mutate(key, |len| *len = len.saturating_add(1));
+        true
+    }
+
+    /// 链表中是否有 `value`
+    pub fn contains(key: &Key, value: Value) -> bool {
+        Storage::contains_key(&(key.clone(), Some(value)))
+    }
+
+    /// 链表的长度
+    pub fn len(key: &Key) -> u32 {
+        Counter::get(key)
+    }
+
+    /// 链表是否为空
+    pub fn is_empty(key: &Key) -> bool {
+        Self::len(key) == 0
+    }
+
+    /// 从头遍历链表, 每一步读取一次存储
+    pub fn iter(key: &Key) -> LinkedListIter<Storage, Key, Value> {
+        Self::iter_after(key, None)
+    }
+
+    /// 从 `after` 之后开始遍历链表, None 表示从头开始. `after` 不在链表中时没有任何结果.
+    pub fn iter_after(key: &Key, after: Option<Value>) -> LinkedListIter<Storage, Key, Value> {
+        let next = Storage::get(&(key.clone(), after)).and_then(|item| item.next);
+        LinkedListIter { key: key.clone(), next, _marker: Default::default() }
+    }
+
+    /// 分页读取 `after` 之后的至多 `limit` 项, 下一页从这一页的最后一项之后开始
+    pub fn page(key: &Key, after: Option<Value>, limit: u32) -> Vec<Value> {
+        Self::iter_after(key, after).take(limit as usize).collect()
+    }
+
+    /// 清空链表, 按顺序返回原来的所有项
+    pub fn take_all(key: &Key) -> Vec<Value> {
+        let mut values = Vec::new();
+
+        let mut cursor = Storage::take(&(key.clone(), None)).and_then(|head| head.next);
+        while let Some(value) = cursor {
+            values.push(value);
+            cursor = Storage::take(&(key.clone(), Some(value))).and_then(|item| item.next);
         }
+
+        Counter::remove(key);
+        values
+    }
+}
+
+/// 链表的惰性迭代器
+pub struct LinkedListIter<Storage, Key, Value> {
+    key: Key,
+    next: Option<Value>,
+    _marker: sp_std::marker::PhantomData<Storage>,
+}
+
+impl<Storage, Key, Value> Iterator for LinkedListIter<Storage, Key, Value>
+where
+    Value: Parameter + Member + Copy,
+    Key: Parameter,
+    Storage: StorageMap<(Key, Option<Value>), LinkedItem<Value>, Query = Option<LinkedItem<Value>>>,
+{
+    type Item = Value;
+
+    fn next(&mut self) -> Option<Value> {
+        let value = self.next?;
+        self.next = Storage::get(&(self.key.clone(), Some(value))).and_then(|item| item.next);
+        Some(value)
     }
-}
\ No newline at end of file
+}
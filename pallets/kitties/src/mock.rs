@@ -1,7 +1,9 @@
-use crate::{ Module, Trait };
+use crate::{ Module, Trait, UniqueAssets };
 use frame_support::{
 	impl_outer_origin, impl_outer_event,
 	parameter_types, weights::Weight,
+	dispatch::{ DispatchError, DispatchResult },
+	traits::{ Currency, Get, OnUnbalanced },
 };
 use sp_runtime::{
 	traits::{ BlakeTwo256, IdentityLookup },
@@ -10,6 +12,7 @@ use sp_runtime::{
 use frame_system as system;
 use sp_core::H256;
 use balances;
+use std::{ cell::RefCell, collections::BTreeMap };
 
 impl_outer_origin! {
 	pub enum Origin for Test {}
@@ -25,6 +28,17 @@ parameter_types! {
 	pub const MaximumBlockLength: u32 = 2 * 1024;
 	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
 	pub const ExistentialDeposit: u64 = 1;
+	pub const MinAncestryDistance: u32 = 2;
+	pub const BreedingCooldown: u64 = 5;
+	pub const MaxAuctionsPerBlock: u32 = 2;
+	pub const MaxOffersPerBlock: u32 = 2;
+	pub const MarketplaceFee: Perbill = Perbill::from_percent(10);
+	pub const CreatorRoyalty: Perbill = Perbill::from_percent(5);
+	pub const KittyDeposit: u64 = 2;
+	pub const MaxNameLength: u32 = 8;
+	pub const NameDeposit: u64 = 3;
+	pub const MaxSireWhitelist: u32 = 3;
+	pub const MaxLeasesPerBlock: u32 = 2;
 }
 
 impl system::Trait for Test {
@@ -79,11 +93,51 @@ impl_outer_event! {
 
 type Randomness = pallet_randomness_collective_flip::Module<Test>;
 
+/// 接收市场手续费的账户
+pub const TREASURY: u64 = 99;
+
+pub struct Treasury;
+impl OnUnbalanced<balances::NegativeImbalance<Test>> for Treasury {
+	fn on_nonzero_unbalanced(amount: balances::NegativeImbalance<Test>) {
+		Balances::resolve_creating(&TREASURY, amount);
+	}
+}
+
+thread_local! {
+	static MUTATION_RATE: RefCell<Perbill> = RefCell::new(Perbill::zero());
+}
+
+/// 默认不突变, 测试可以用 `set_mutation_rate` 修改
+pub struct MutationRate;
+impl Get<Perbill> for MutationRate {
+	fn get() -> Perbill {
+		MUTATION_RATE.with(|rate| *rate.borrow())
+	}
+}
+
+pub fn set_mutation_rate(rate: Perbill) {
+	MUTATION_RATE.with(|v| *v.borrow_mut() = rate);
+}
+
 impl Trait for Test {
 	type Event = TestEvent;
 	type KittyIndex = u32;
 	type Currency = balances::Module<Test>;
 	type Randomness = Randomness;
+	type MinAncestryDistance = MinAncestryDistance;
+	type BreedingCooldown = BreedingCooldown;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+	type MaxOffersPerBlock = MaxOffersPerBlock;
+	type MarketplaceFee = MarketplaceFee;
+	type Treasury = Treasury;
+	type CreatorRoyalty = CreatorRoyalty;
+	type KittyDeposit = KittyDeposit;
+	type MutationRate = MutationRate;
+	type MaxNameLength = MaxNameLength;
+	type NameDeposit = NameDeposit;
+	type MaxSireWhitelist = MaxSireWhitelist;
+	type MaxLeasesPerBlock = MaxLeasesPerBlock;
+	type WeightInfo = ();
 }
 
 pub type Kitties = Module<Test>;
@@ -97,7 +151,121 @@ pub fn last_event() -> TestEvent {
 		.event
 }
 
+/// 测试结束时检查存储一致性的测试环境
+pub struct TestExt {
+	ext: sp_io::TestExternalities,
+	check: bool,
+}
+
+impl TestExt {
+	/// 不检查一致性, 只用于故意构造了不完整存储的测试, 例如迁移旧版本的数据
+	pub fn without_checks(mut self) -> Self {
+		self.check = false;
+		self
+	}
+
+	pub fn execute_with<R>(mut self, execute: impl FnOnce() -> R) -> R {
+		let check = self.check;
+		self.ext.execute_with(|| {
+			let result = execute();
+			if check {
+				Kitties::do_try_state().expect("kitties storage is inconsistent");
+			}
+			result
+		})
+	}
+}
+
 // Build genesis storage according to the mock runtime.
-pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+pub fn new_test_ext() -> TestExt {
+	let ext = system::GenesisConfig::default().build_storage::<Test>().unwrap().into();
+	TestExt { ext, check: true }
+}
+
+// 带有创世余额和创世小猫的测试环境
+pub fn new_test_ext_with_genesis(
+	balances: Vec<(u64, u64)>,
+	kitties: Vec<(u64, [u8; 16], Option<u64>)>,
+) -> TestExt {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	balances::GenesisConfig::<Test> { balances }.assimilate_storage(&mut t).unwrap();
+	crate::GenesisConfig::<Test> { kitties }.assimilate_storage(&mut t).unwrap();
+	TestExt { ext: t.into(), check: true }
+}
+
+thread_local! {
+	static ASSETS: RefCell<BTreeMap<u32, (u64, bool)>> = RefCell::new(BTreeMap::new());
+}
+
+/// 用于测试的 `UniqueAssets` 实现, 账户是 `u64`, 资产编号是 `u32`, 数据保存在当前线程中
+pub struct MockUniqueAssets;
+
+impl MockUniqueAssets {
+	/// 创建或者覆盖一个资产
+	pub fn mint(owner: u64, asset: u32) {
+		ASSETS.with(|assets| assets.borrow_mut().insert(asset, (owner, false)));
+	}
+
+	/// 删除一个资产
+	pub fn burn(asset: u32) {
+		ASSETS.with(|assets| assets.borrow_mut().remove(&asset));
+	}
+
+	/// 删除所有资产
+	pub fn reset() {
+		ASSETS.with(|assets| assets.borrow_mut().clear());
+	}
+}
+
+impl UniqueAssets<u64> for MockUniqueAssets {
+	type AssetId = u32;
+
+	fn owner_of(asset: u32) -> Option<u64> {
+		ASSETS.with(|assets| assets.borrow().get(&asset).map(|(owner, _)| *owner))
+	}
+
+	fn transfer(from: &u64, to: &u64, asset: u32) -> DispatchResult {
+		ASSETS.with(|assets| match assets.borrow_mut().get_mut(&asset) {
+			Some((owner, false)) if owner == from => {
+				*owner = *to;
+				Ok(())
+			}
+			Some((_, true)) => Err(DispatchError::Other("asset is locked")),
+			_ => Err(DispatchError::Other("not the owner of the asset")),
+		})
+	}
+
+	fn total_supply() -> u64 {
+		ASSETS.with(|assets| assets.borrow().len() as u64)
+	}
+
+	fn assets_of(owner: &u64) -> Vec<u32> {
+		ASSETS.with(|assets| {
+			assets.borrow().iter().filter(|(_, (o, _))| o == owner).map(|(asset, _)| *asset).collect()
+		})
+	}
+
+	fn lock(owner: &u64, asset: u32) -> DispatchResult {
+		ASSETS.with(|assets| match assets.borrow_mut().get_mut(&asset) {
+			Some((o, locked)) if o == owner && !*locked => {
+				*locked = true;
+				Ok(())
+			}
+			_ => Err(DispatchError::Other("cannot lock the asset")),
+		})
+	}
+
+	fn unlock(asset: u32) -> DispatchResult {
+		ASSETS.with(|assets| match assets.borrow_mut().get_mut(&asset) {
+			Some((_, locked)) if *locked => {
+				*locked = false;
+				Ok(())
+			}
+			_ => Err(DispatchError::Other("asset is not locked")),
+		})
+	}
+
+	fn is_locked(asset: u32) -> bool {
+		ASSETS.with(|assets| assets.borrow().get(&asset).map_or(false, |(_, locked)| *locked))
+	}
 }
@@ -0,0 +1,35 @@
+//! 不可替代资产的通用接口.
+//!
+//! 其他模块通过 `UniqueAssets` 持有和转移小猫, 不需要依赖 `pallet_kitties` 的存储细节.
+//! 模块可以锁定资产来持有它, 锁定期间拥有者不变, 但资产不能转移, 直到模块解除锁定.
+
+use frame_support::{ dispatch::DispatchResult, Parameter };
+use sp_runtime::traits::Member;
+use sp_std::prelude::*;
+
+/// 一组不可替代的资产, 每个资产有唯一的编号和一个拥有者
+pub trait UniqueAssets<AccountId> {
+    /// 资产的编号
+    type AssetId: Parameter + Member + Copy;
+
+    /// 资产的拥有者, 资产不存在时返回 None
+    fn owner_of(asset: Self::AssetId) -> Option<AccountId>;
+
+    /// 把 `from` 的资产转给 `to`, 调用者负责检查权限
+    fn transfer(from: &AccountId, to: &AccountId, asset: Self::AssetId) -> DispatchResult;
+
+    /// 现存的资产数量
+    fn total_supply() -> u64;
+
+    /// 某个账户拥有的全部资产
+    fn assets_of(owner: &AccountId) -> Vec<Self::AssetId>;
+
+    /// 锁定 `owner` 的资产, 拥有者不变, 锁定期间 `transfer` 会失败. 调用者负责检查权限
+    fn lock(owner: &AccountId, asset: Self::AssetId) -> DispatchResult;
+
+    /// 解除资产的锁定
+    fn unlock(asset: Self::AssetId) -> DispatchResult;
+
+    /// 资产是否被锁定
+    fn is_locked(asset: Self::AssetId) -> bool;
+}
@@ -1,10 +1,19 @@
-use crate::{ Event, Error, mock::* };
+use crate::{
+    AuctionKind, Event, Error, Kitty, KittiesCount, KittyOwners, KittyParents, KittyPartners, KittyPrices, Listing,
+    Lease, LeaseTerms, OwnedKitties, OwnedKittiesList, Offer, Releases, SireListing, StorageVersion, TraitCounts,
+    KITTIES_ID, KITTIES_PAGE_SIZE, MAX_ANCESTRY_DEPTH,
+    mock::*,
+    genes::{ self, Color, EyeShape, Gene, Pattern, RarityTier },
+    nft::UniqueAssets,
+};
 use frame_support::{
-    assert_noop, assert_ok,
-    traits::{ OnFinalize, OnInitialize }
+    assert_noop, assert_ok, StorageValue, StorageMap, StorageDoubleMap,
+    traits::{ OnFinalize, OnInitialize, OnRuntimeUpgrade }
 };
 use frame_system::{ EventRecord, Phase };
-use frame_support::traits::Currency;
+use sp_runtime::Perbill;
+use std::collections::VecDeque;
+use frame_support::traits::{ Currency, Get, LockableCurrency, LockIdentifier, WithdrawReasons };
 
 fn run_to_block(n: u64) {
     while System::block_number() < n {
@@ -40,6 +49,7 @@ fn transfer_kitty_works() {
         run_to_block(10);
 
         let _ = Balances::deposit_creating(&1, 10);
+        let _ = Balances::deposit_creating(&2, 10);
 
         let _ = Kitties::create(Origin::signed(1));
 
@@ -63,6 +73,7 @@ fn transfer_kitties_work() {
         run_to_block(10);
 
         let _ = Balances::deposit_creating(&1, 10);
+        let _ = Balances::deposit_creating(&2, 10);
 
         assert_ok!(Kitties::create(Origin::signed(1)));
         let id = Kitties::kitties_count();
@@ -123,4 +134,1950 @@ fn breed_kitty_fail_when_not_owner() {
 
         assert_noop!(Kitties::breed(Origin::signed(2), 0, 1), Error::<Test>::RequireOwner);
     })
-}
\ No newline at end of file
+}
+
+#[test]
+fn kitty_info_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 10);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+        assert_ok!(Kitties::ask(Origin::signed(1), 2, Some(8)));
+
+        let info = Kitties::kitty_info(2).expect("kitty exists");
+        assert_eq!(info.id, 2);
+        assert_eq!(info.owner, 1);
+        assert_eq!(info.price, Some(8));
+        assert_eq!(info.parents, Some((0, 1)));
+        assert_eq!(info.dna, Kitties::kitties(2).unwrap().dna);
+
+        assert_eq!(Kitties::kitty_info(0).unwrap().parents, None);
+        assert_eq!(Kitties::kitty_info(3), None);
+    })
+}
+
+#[test]
+fn kitties_of_paginates() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let total = KITTIES_PAGE_SIZE + 3;
+        for _ in 0..total {
+            assert_ok!(Kitties::create(Origin::signed(1)));
+        }
+
+        let first_page = Kitties::kitties_of(&1, 0);
+        assert_eq!(first_page.len(), KITTIES_PAGE_SIZE as usize);
+        assert_eq!(first_page[0].id, 0);
+
+        let second_page = Kitties::kitties_of(&1, 1);
+        let ids: Vec<u32> = second_page.iter().map(|info| info.id).collect();
+        assert_eq!(ids, (KITTIES_PAGE_SIZE..total).collect::<Vec<u32>>());
+
+        assert!(Kitties::kitties_of(&1, 2).is_empty());
+        assert!(Kitties::kitties_of(&2, 0).is_empty());
+    })
+}
+
+#[test]
+fn breed_persists_lineage() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+        run_to_block(20);
+        assert_ok!(Kitties::breed(Origin::signed(1), 1, 0));
+
+        assert_eq!(Kitties::kitty_children(0), vec![2, 3]);
+        assert_eq!(Kitties::kitty_children(1), vec![2, 3]);
+        assert_eq!(Kitties::kitty_partners(0), vec![1]);
+        assert_eq!(KittyPartners::<Test>::get(0, 1), 2);
+        assert_eq!(Kitties::kitty_info(0).unwrap().children, vec![2, 3]);
+    })
+}
+
+#[test]
+fn breed_increments_generation() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+        assert_ok!(Kitties::breed(Origin::signed(1), 3, 2));
+
+        assert_eq!(Kitties::kitties(0).unwrap().generation, 0);
+        assert_eq!(Kitties::kitties(3).unwrap().generation, 1);
+        assert_eq!(Kitties::kitties(4).unwrap().generation, 2);
+        assert_eq!(Kitties::kitty_info(4).unwrap().generation, 2);
+    })
+}
+
+#[test]
+fn ancestors_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+        assert_ok!(Kitties::breed(Origin::signed(1), 3, 2));
+
+        assert_eq!(Kitties::ancestors(4, 1), vec![vec![2, 3]]);
+        assert_eq!(Kitties::ancestors(4, 5), vec![vec![2, 3], vec![0, 1]]);
+        assert!(Kitties::ancestors(0, 5).is_empty());
+        assert!(Kitties::ancestors(4, 0).is_empty());
+    })
+}
+
+#[test]
+fn max_ancestors_works() {
+    assert_eq!(Kitties::max_ancestors(0), 0);
+    assert_eq!(Kitties::max_ancestors(1), 4);
+    assert_eq!(Kitties::max_ancestors(2), 12);
+    assert_eq!(Kitties::max_ancestors(u32::max_value()), Kitties::max_ancestors(MAX_ANCESTRY_DEPTH));
+}
+
+#[test]
+fn migrate_to_v2_works() {
+    new_test_ext().without_checks().execute_with(|| {
+        for kitty_id in 0u32..3 {
+            let key = crate::Kitties::<Test>::hashed_key_for(kitty_id);
+            frame_support::storage::unhashed::put(&key, &[kitty_id as u8; 16]);
+        }
+        KittiesCount::<Test>::put(3);
+        KittyParents::<Test>::insert(2, (0, 1));
+        StorageVersion::put(Releases::V1_0_0);
+
+        Kitties::migrate_to_v2();
+
+        assert_eq!(Kitties::kitties(0), Some(Kitty { dna: [0; 16], generation: 0 }));
+        assert_eq!(Kitties::kitties(2), Some(Kitty { dna: [2; 16], generation: 1 }));
+        assert_eq!(Kitties::kitty_children(0), vec![2]);
+        assert_eq!(Kitties::kitty_partners(1), vec![0]);
+        assert_eq!(StorageVersion::get(), Releases::V2_0_0);
+    })
+}
+
+#[test]
+fn breed_fails_with_parent_or_sibling() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+        run_to_block(20);
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+
+        // 孩子和父母
+        assert_noop!(Kitties::breed(Origin::signed(1), 2, 0), Error::<Test>::TooCloselyRelated);
+        // 兄弟姐妹
+        assert_noop!(Kitties::breed(Origin::signed(1), 2, 3), Error::<Test>::TooCloselyRelated);
+    })
+}
+
+#[test]
+fn breed_fails_with_grandparent_within_distance() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+        assert_ok!(Kitties::breed(Origin::signed(1), 3, 2));
+
+        // 4 的祖父母是 0 和 1, 在血缘距离 2 之内
+        assert!(Kitties::is_related(4, 0, 2));
+        assert!(!Kitties::is_related(4, 0, 1));
+        assert_noop!(Kitties::breed(Origin::signed(1), 4, 0), Error::<Test>::TooCloselyRelated);
+    })
+}
+
+#[test]
+fn breed_fails_during_cooldown() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+        assert_eq!(Kitties::next_breeding(0), 15);
+
+        assert_noop!(Kitties::breed(Origin::signed(1), 0, 2), Error::<Test>::KittyOnCooldown);
+        assert_noop!(Kitties::breed(Origin::signed(1), 2, 1), Error::<Test>::KittyOnCooldown);
+
+        run_to_block(15);
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 2));
+    })
+}
+
+#[test]
+fn cooldown_grows_with_generation() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        for _ in 0..4 {
+            let _ = Kitties::create(Origin::signed(1));
+        }
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+        assert_ok!(Kitties::breed(Origin::signed(1), 2, 3));
+        // 4 和 5 是第 1 代
+        assert_ok!(Kitties::breed(Origin::signed(1), 4, 5));
+
+        assert_eq!(Kitties::cooldown_of(1), 10);
+        assert_eq!(Kitties::next_breeding(4), 20);
+        assert_eq!(Kitties::next_breeding(0), 15);
+    })
+}
+
+fn locked(who: u64) -> Vec<(LockIdentifier, u64)> {
+    Balances::locks(who).into_iter().map(|lock| (lock.id, lock.amount)).collect()
+}
+
+#[test]
+fn deposit_lock_tracks_owned_kitties() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 10);
+        let _ = Balances::deposit_creating(&2, 10);
+
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_eq!(locked(1), vec![(KITTIES_ID, 4)]);
+
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+        assert_eq!(locked(1), vec![(KITTIES_ID, 2)]);
+        assert_eq!(locked(2), vec![(KITTIES_ID, 2)]);
+
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 1));
+        assert!(locked(1).is_empty());
+        assert_eq!(locked(2), vec![(KITTIES_ID, 4)]);
+        assert_eq!(Kitties::owned_kitties_count(2), 2);
+    })
+}
+
+#[test]
+fn buy_moves_deposit_lock() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 10);
+        let _ = Balances::deposit_creating(&2, 20);
+
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(5)));
+        assert_ok!(Kitties::buy(Origin::signed(2), 0, 5, 0));
+
+        assert!(locked(1).is_empty());
+        assert_eq!(locked(2), vec![(KITTIES_ID, 2)]);
+        assert_eq!(Balances::free_balance(1), 15);
+    })
+}
+
+#[test]
+fn deposit_required_for_new_kitty() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 5);
+        let _ = Balances::deposit_creating(&2, 3);
+
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_noop!(Kitties::create(Origin::signed(1)), Error::<Test>::BalanceNotEnough);
+
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+        assert_noop!(Kitties::transfer(Origin::signed(1), 2, 1), Error::<Test>::BalanceNotEnough);
+
+        assert_ok!(Kitties::ask(Origin::signed(1), 1, Some(2)));
+        assert_noop!(Kitties::buy(Origin::signed(2), 1, 2, 0), Error::<Test>::BalanceNotEnough);
+    })
+}
+
+#[test]
+fn migrate_to_v3_cleans_stranded_locks() {
+    new_test_ext().without_checks().execute_with(|| {
+        let _ = Balances::deposit_creating(&1, 10);
+        let _ = Balances::deposit_creating(&2, 10);
+
+        // 小猫 0 从 1 转给了 2, 旧版本在两个账户上都留下了锁
+        for kitty_id in 0u32..2 {
+            crate::Kitties::<Test>::insert(kitty_id, Kitty { dna: [0; 16], generation: 0 });
+        }
+        KittiesCount::<Test>::put(2);
+        KittyOwners::<Test>::insert(0, 2);
+        KittyOwners::<Test>::insert(1, 2);
+        let reasons = WithdrawReasons::all();
+        Balances::set_lock(Kitties::legacy_lock_id(&1, 0), &1, 5, reasons);
+        Balances::set_lock(Kitties::legacy_lock_id(&2, 0), &2, 5, reasons);
+        Balances::set_lock(Kitties::legacy_lock_id(&2, 1), &2, 5, reasons);
+        StorageVersion::put(Releases::V2_0_0);
+
+        Kitties::migrate_to_v3();
+
+        assert_eq!(locked(2), vec![(KITTIES_ID, 4)]);
+        assert_eq!(Kitties::owned_kitties_count(2), 2);
+        assert_eq!(StorageVersion::get(), Releases::V3_0_0);
+        assert!(Kitties::legacy_lock_sweep().is_some());
+
+        // 原拥有者身上遗留的锁在之后的区块中清理
+        Kitties::on_initialize(1);
+        assert!(Balances::locks(1).is_empty());
+        assert_eq!(locked(2), vec![(KITTIES_ID, 4)]);
+        assert_eq!(Kitties::legacy_lock_sweep(), None);
+    })
+}
+
+#[test]
+fn legacy_lock_sweep_is_bounded() {
+    new_test_ext().without_checks().execute_with(|| {
+        let _ = Balances::deposit_creating(&1, 10);
+        let _ = Balances::deposit_creating(&2, 10);
+
+        crate::Kitties::<Test>::insert(0, Kitty { dna: [0; 16], generation: 0 });
+        crate::Kitties::<Test>::insert(1, Kitty { dna: [0; 16], generation: 0 });
+        KittiesCount::<Test>::put(2);
+        KittyOwners::<Test>::insert(0, 2);
+        KittyOwners::<Test>::insert(1, 2);
+        let reasons = WithdrawReasons::all();
+        Balances::set_lock(Kitties::legacy_lock_id(&1, 0), &1, 5, reasons);
+        Balances::set_lock(Kitties::legacy_lock_id(&1, 1), &1, 5, reasons);
+        StorageVersion::put(Releases::V2_0_0);
+
+        Kitties::migrate_to_v3();
+
+        // 每次最多走 1 步, 两个账户各两只小猫加上换账户, 需要多次才能完成
+        let mut rounds = 0;
+        while Kitties::legacy_lock_sweep().is_some() {
+            assert!(Kitties::sweep_legacy_locks(1) <= 1);
+            rounds += 1;
+            assert!(rounds < 20);
+        }
+        assert!(rounds > 1);
+        assert!(Balances::locks(1).is_empty());
+        assert_eq!(Kitties::sweep_legacy_locks(1), 0);
+    })
+}
+
+#[test]
+fn create_auction_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(8)));
+
+        assert_noop!(
+            Kitties::create_auction(Origin::signed(2), 0, AuctionKind::English, 10, 20, 20),
+            Error::<Test>::RequireOwner
+        );
+        assert_noop!(
+            Kitties::create_auction(Origin::signed(1), 0, AuctionKind::English, 10, 20, 10),
+            Error::<Test>::InvalidAuctionEnd
+        );
+        assert_noop!(
+            Kitties::create_auction(Origin::signed(1), 0, AuctionKind::Dutch, 10, 20, 20),
+            Error::<Test>::InvalidReservePrice
+        );
+
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 0, AuctionKind::English, 10, 20, 20));
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::AuctionCreated(1, 0, 10, 20)),
+        );
+        assert_eq!(Kitties::kitty_price(0), None);
+        assert_eq!(Kitties::auctions_ending(20), vec![0]);
+
+        assert_noop!(
+            Kitties::create_auction(Origin::signed(1), 0, AuctionKind::English, 10, 20, 30),
+            Error::<Test>::KittyInAuction
+        );
+    })
+}
+
+#[test]
+fn create_auction_fails_when_too_many_end_in_block() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        for kitty_id in 0..3 {
+            let _ = Kitties::create(Origin::signed(1));
+            if kitty_id < 2 {
+                assert_ok!(Kitties::create_auction(Origin::signed(1), kitty_id, AuctionKind::English, 10, 10, 20));
+            }
+        }
+
+        assert_noop!(
+            Kitties::create_auction(Origin::signed(1), 2, AuctionKind::English, 10, 10, 20),
+            Error::<Test>::TooManyAuctions
+        );
+    })
+}
+
+#[test]
+fn auctioned_kitty_is_not_transferable() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 0, AuctionKind::English, 10, 10, 20));
+
+        assert_noop!(Kitties::transfer(Origin::signed(1), 2, 0), Error::<Test>::KittyInAuction);
+        assert_noop!(Kitties::ask(Origin::signed(1), 0, Some(5)), Error::<Test>::KittyInAuction);
+        assert_noop!(Kitties::buy(Origin::signed(2), 0, 5, 0), Error::<Test>::NotForSale);
+    })
+}
+
+#[test]
+fn english_auction_bids_reserve_funds() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+        let _ = Balances::deposit_creating(&3, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 0, AuctionKind::English, 10, 20, 20));
+
+        assert_noop!(Kitties::bid(Origin::signed(1), 0, 10), Error::<Test>::BidOnOwnKitty);
+        assert_noop!(Kitties::bid(Origin::signed(2), 0, 9), Error::<Test>::BidTooLow);
+
+        assert_ok!(Kitties::bid(Origin::signed(2), 0, 15));
+        assert_eq!(Balances::reserved_balance(2), 15);
+        assert_eq!(last_event(), TestEvent::kitties_event(Event::<Test>::Bid(2, 0, 15)));
+
+        assert_noop!(Kitties::bid(Origin::signed(3), 0, 15), Error::<Test>::BidTooLow);
+        assert_noop!(Kitties::cancel_auction(Origin::signed(1), 0), Error::<Test>::AuctionHasBids);
+
+        assert_ok!(Kitties::bid(Origin::signed(3), 0, 25));
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Balances::reserved_balance(3), 25);
+        assert!(System::events().iter().any(|record|
+            record.event == TestEvent::kitties_event(Event::<Test>::Outbid(2, 0, 15))
+        ));
+    })
+}
+
+#[test]
+fn english_auction_settles_on_finalize() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 0, AuctionKind::English, 10, 20, 20));
+        assert_ok!(Kitties::bid(Origin::signed(2), 0, 30));
+
+        run_to_block(20);
+        assert_eq!(Kitties::kitty_owner(0), Some(1));
+
+        run_to_block(21);
+        assert_eq!(Kitties::kitty_owner(0), Some(2));
+        assert_eq!(Kitties::auction(0), None);
+        assert!(Kitties::auctions_ending(20).is_empty());
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Balances::free_balance(2), 70);
+        assert_eq!(Balances::free_balance(1), 127);
+        assert_eq!(Balances::free_balance(TREASURY), 3);
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::AuctionSettled(0, 1, Some(2), 30)),
+        );
+    })
+}
+
+#[test]
+fn english_auction_below_reserve_refunds_bidder() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 0, AuctionKind::English, 10, 20, 20));
+        assert_ok!(Kitties::bid(Origin::signed(2), 0, 15));
+
+        run_to_block(21);
+        assert_eq!(Kitties::kitty_owner(0), Some(1));
+        assert_eq!(Balances::free_balance(2), 100);
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::AuctionSettled(0, 1, None, 0)),
+        );
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+    })
+}
+
+#[test]
+fn dutch_auction_price_decays() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 0, AuctionKind::Dutch, 50, 10, 20));
+
+        let auction = Kitties::auction(0).unwrap();
+        assert_eq!(Kitties::dutch_price(&auction, 10), 50);
+        assert_eq!(Kitties::dutch_price(&auction, 15), 30);
+        assert_eq!(Kitties::dutch_price(&auction, 20), 10);
+        assert_eq!(Kitties::dutch_price(&auction, 30), 10);
+
+        run_to_block(15);
+        assert_noop!(Kitties::bid(Origin::signed(2), 0, 29), Error::<Test>::BidTooLow);
+        assert_ok!(Kitties::bid(Origin::signed(2), 0, 40));
+
+        // 按当前价格成交, 不是出价
+        assert_eq!(Kitties::kitty_owner(0), Some(2));
+        assert_eq!(Balances::free_balance(2), 70);
+        assert_eq!(Balances::free_balance(1), 127);
+        assert_eq!(Balances::free_balance(TREASURY), 3);
+        assert_eq!(Kitties::auction(0), None);
+        assert!(Kitties::auctions_ending(20).is_empty());
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::AuctionSettled(0, 1, Some(2), 30)),
+        );
+    })
+}
+
+#[test]
+fn cancel_auction_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 0, AuctionKind::Dutch, 50, 10, 20));
+
+        assert_noop!(Kitties::cancel_auction(Origin::signed(2), 0), Error::<Test>::RequireOwner);
+        assert_ok!(Kitties::cancel_auction(Origin::signed(1), 0));
+        assert_eq!(last_event(), TestEvent::kitties_event(Event::<Test>::AuctionCancelled(1, 0)));
+        assert_eq!(Kitties::auction(0), None);
+        assert!(Kitties::auctions_ending(20).is_empty());
+        assert_noop!(Kitties::cancel_auction(Origin::signed(1), 0), Error::<Test>::AuctionNotExist);
+    })
+}
+
+#[test]
+fn make_offer_reserves_funds() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+
+        assert_noop!(Kitties::make_offer(Origin::signed(2), 1, 20, 20), Error::<Test>::InvalidKittyId);
+        assert_noop!(Kitties::make_offer(Origin::signed(1), 0, 20, 20), Error::<Test>::OfferOnOwnKitty);
+        assert_noop!(Kitties::make_offer(Origin::signed(2), 0, 20, 10), Error::<Test>::InvalidOfferExpiry);
+        assert_noop!(Kitties::make_offer(Origin::signed(2), 0, 99, 20), Error::<Test>::BalanceNotEnough);
+
+        assert_ok!(Kitties::make_offer(Origin::signed(2), 0, 20, 20));
+        assert_eq!(Balances::reserved_balance(2), 20);
+        assert_eq!(Kitties::offer(0, 2), Some(Offer { amount: 20, expiry: 20 }));
+        assert_eq!(Kitties::offer_expiries(20), vec![(0, 2)]);
+        assert_eq!(last_event(), TestEvent::kitties_event(Event::<Test>::OfferMade(2, 0, 20, 20)));
+
+        assert_noop!(Kitties::make_offer(Origin::signed(2), 0, 30, 20), Error::<Test>::OfferAlreadyExist);
+    })
+}
+
+#[test]
+fn make_offer_fails_when_too_many_expire_in_block() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        for who in 2..5 {
+            let _ = Balances::deposit_creating(&who, 100);
+        }
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::make_offer(Origin::signed(2), 0, 20, 20));
+        assert_ok!(Kitties::make_offer(Origin::signed(3), 0, 20, 20));
+        assert_noop!(Kitties::make_offer(Origin::signed(4), 0, 20, 20), Error::<Test>::TooManyOffers);
+        assert_ok!(Kitties::make_offer(Origin::signed(4), 0, 20, 21));
+    })
+}
+
+#[test]
+fn accept_offer_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+        let _ = Balances::deposit_creating(&3, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(50)));
+        assert_ok!(Kitties::make_offer(Origin::signed(2), 0, 20, 20));
+        assert_ok!(Kitties::make_offer(Origin::signed(3), 0, 30, 20));
+
+        assert_noop!(Kitties::accept_offer(Origin::signed(2), 0, 3), Error::<Test>::RequireOwner);
+        assert_noop!(Kitties::accept_offer(Origin::signed(1), 0, 4), Error::<Test>::OfferNotExist);
+
+        assert_ok!(Kitties::accept_offer(Origin::signed(1), 0, 3));
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::OfferAccepted(1, 3, 0, 30)),
+        );
+        assert_eq!(Kitties::kitty_owner(0), Some(3));
+        assert_eq!(Kitties::kitty_price(0), None);
+        assert_eq!(Balances::free_balance(1), 127);
+        assert_eq!(Balances::free_balance(TREASURY), 3);
+        assert_eq!(Balances::free_balance(3), 70);
+        assert_eq!(Balances::reserved_balance(3), 0);
+        assert_eq!(Kitties::offer(0, 3), None);
+        assert_eq!(Kitties::offer_expiries(20), vec![(0, 2)]);
+
+        // 其他报价保留, 新的拥有者可以接受
+        assert_eq!(Balances::reserved_balance(2), 20);
+        assert_ok!(Kitties::accept_offer(Origin::signed(3), 0, 2));
+        assert_eq!(Kitties::kitty_owner(0), Some(2));
+    })
+}
+
+#[test]
+fn withdraw_offer_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::make_offer(Origin::signed(2), 0, 20, 20));
+
+        assert_noop!(Kitties::withdraw_offer(Origin::signed(3), 0), Error::<Test>::OfferNotExist);
+
+        assert_ok!(Kitties::withdraw_offer(Origin::signed(2), 0));
+        assert_eq!(last_event(), TestEvent::kitties_event(Event::<Test>::OfferWithdrawn(2, 0, 20)));
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Kitties::offer(0, 2), None);
+        assert!(Kitties::offer_expiries(20).is_empty());
+    })
+}
+
+#[test]
+fn expired_offers_are_unreserved() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::make_offer(Origin::signed(2), 0, 20, 20));
+
+        run_to_block(19);
+        assert_eq!(Balances::reserved_balance(2), 20);
+
+        run_to_block(20);
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Kitties::offer(0, 2), None);
+        assert!(Kitties::offer_expiries(20).is_empty());
+        assert_eq!(last_event(), TestEvent::kitties_event(Event::<Test>::OfferExpired(2, 0, 20)));
+        assert_noop!(Kitties::accept_offer(Origin::signed(1), 0, 2), Error::<Test>::OfferNotExist);
+    })
+}
+
+#[test]
+fn buy_pays_fee_and_royalty() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+        let _ = Balances::deposit_creating(&3, 200);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_eq!(Kitties::kitty_creator(0), Some((1, CreatorRoyalty::get())));
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+        assert_ok!(Kitties::ask(Origin::signed(2), 0, Some(100)));
+
+        assert_ok!(Kitties::buy(Origin::signed(3), 0, 100, 0));
+
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::Sold(2, 3, 0, 100, 10, 5)),
+        );
+        assert_eq!(Balances::free_balance(3), 100);
+        assert_eq!(Balances::free_balance(2), 185);
+        assert_eq!(Balances::free_balance(1), 105);
+        assert_eq!(Balances::free_balance(TREASURY), 10);
+    })
+}
+
+#[test]
+fn accept_offer_pays_fee_and_royalty() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+        let _ = Balances::deposit_creating(&3, 200);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+        assert_ok!(Kitties::make_offer(Origin::signed(3), 0, 100, 20));
+
+        assert_ok!(Kitties::accept_offer(Origin::signed(2), 0, 3));
+
+        assert_eq!(Balances::free_balance(3), 100);
+        assert_eq!(Balances::reserved_balance(3), 0);
+        assert_eq!(Balances::free_balance(2), 185);
+        assert_eq!(Balances::free_balance(1), 105);
+        assert_eq!(Balances::free_balance(TREASURY), 10);
+    })
+}
+
+#[test]
+fn english_auction_pays_fee_and_royalty() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+        let _ = Balances::deposit_creating(&3, 200);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+        assert_ok!(Kitties::create_auction(Origin::signed(2), 0, AuctionKind::English, 10, 20, 20));
+        assert_ok!(Kitties::bid(Origin::signed(3), 0, 100));
+
+        run_to_block(21);
+
+        assert_eq!(Kitties::kitty_owner(0), Some(3));
+        assert_eq!(Balances::free_balance(3), 100);
+        assert_eq!(Balances::reserved_balance(3), 0);
+        assert_eq!(Balances::free_balance(2), 185);
+        assert_eq!(Balances::free_balance(1), 105);
+        assert_eq!(Balances::free_balance(TREASURY), 10);
+    })
+}
+
+#[test]
+fn dutch_auction_pays_fee_and_royalty() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+        let _ = Balances::deposit_creating(&3, 200);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+        assert_ok!(Kitties::create_auction(Origin::signed(2), 0, AuctionKind::Dutch, 100, 10, 20));
+        assert_ok!(Kitties::bid(Origin::signed(3), 0, 100));
+
+        assert_eq!(Kitties::kitty_owner(0), Some(3));
+        assert_eq!(Balances::free_balance(3), 100);
+        assert_eq!(Balances::free_balance(2), 185);
+        assert_eq!(Balances::free_balance(1), 105);
+        assert_eq!(Balances::free_balance(TREASURY), 10);
+    })
+}
+
+#[test]
+fn buy_from_creator_pays_no_royalty() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 200);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(100)));
+
+        assert_ok!(Kitties::buy(Origin::signed(2), 0, 100, 0));
+
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::Sold(1, 2, 0, 100, 10, 0)),
+        );
+        assert_eq!(Balances::free_balance(1), 190);
+        assert_eq!(Balances::free_balance(TREASURY), 10);
+    })
+}
+
+#[test]
+fn royalty_of_reaped_creator_goes_to_treasury() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+        let _ = Balances::deposit_creating(&3, 200);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+        let _ = Balances::make_free_balance_be(&1, 0);
+        assert!(!System::account_exists(&1));
+
+        assert_ok!(Kitties::ask(Origin::signed(2), 0, Some(100)));
+        assert_ok!(Kitties::buy(Origin::signed(3), 0, 100, 0));
+
+        assert_eq!(Balances::free_balance(1), 0);
+        assert_eq!(Balances::free_balance(2), 185);
+        assert_eq!(Balances::free_balance(TREASURY), 15);
+    })
+}
+
+#[test]
+fn buy_fails_when_listing_changed() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(10)));
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::Ask(1, 0, Some(Listing { price: 10, nonce: 0 }))),
+        );
+
+        // 拥有者在买家出手之前抬价
+        assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(50)));
+        assert_noop!(Kitties::buy(Origin::signed(2), 0, 60, 0), Error::<Test>::ListingChanged);
+
+        // 下架之后重新以原价挂单, 旧的 nonce 也不能使用
+        assert_ok!(Kitties::ask(Origin::signed(1), 0, None));
+        assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(10)));
+        assert_noop!(Kitties::buy(Origin::signed(2), 0, 10, 0), Error::<Test>::ListingChanged);
+        assert_noop!(Kitties::buy(Origin::signed(2), 0, 10, 1), Error::<Test>::ListingChanged);
+
+        assert_eq!(Kitties::kitty_info(0).unwrap().listing_nonce, Some(2));
+        assert_ok!(Kitties::buy(Origin::signed(2), 0, 10, 2));
+        assert_eq!(Kitties::kitty_owner(0), Some(2));
+    })
+}
+
+#[test]
+fn transfer_clears_listing() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+        let _ = Balances::deposit_creating(&3, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(10)));
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+
+        assert_eq!(Kitties::kitty_price(0), None);
+        assert_noop!(Kitties::buy(Origin::signed(3), 0, 10, 0), Error::<Test>::NotForSale);
+    })
+}
+
+#[test]
+fn buy_fails_when_buyer_owns_kitty() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(10)));
+
+        assert_noop!(Kitties::buy(Origin::signed(1), 0, 10, 0), Error::<Test>::BuyOwnKitty);
+    })
+}
+
+#[test]
+fn migrate_to_v4_works() {
+    new_test_ext().without_checks().execute_with(|| {
+        let key = KittyPrices::<Test>::hashed_key_for(3);
+        frame_support::storage::unhashed::put(&key, &7u64);
+        StorageVersion::put(Releases::V3_0_0);
+
+        Kitties::migrate_to_v4();
+
+        assert_eq!(Kitties::kitty_price(3), Some(Listing { price: 7, nonce: 0 }));
+        assert_eq!(Kitties::next_listing_nonce(), 1);
+        assert_eq!(StorageVersion::get(), Releases::V4_0_0);
+    })
+}
+
+#[test]
+fn decode_traits_works() {
+    let mut dna = [0u8; 16];
+    // 显性的 Ginger 压过隐性的 White
+    dna[0] = 0x82;
+    dna[1] = 0x01;
+    // 都是隐性时取值较小的 Tabby 表达出来
+    dna[2] = 0x05;
+    dna[3] = 0x01;
+    // 都是显性时同样取值较小的表达出来
+    dna[4] = 0x83;
+    dna[5] = 0x82;
+    // 隐性携带传说稀有度
+    dna[6] = 0x7f;
+    dna[7] = 0x10;
+
+    let traits = genes::KittyTraits::from_dna(&dna);
+    assert_eq!(traits.color, Gene { expressed: Color::Ginger, hidden: Color::White, dominant: true });
+    assert_eq!(traits.pattern, Gene { expressed: Pattern::Tabby, hidden: Pattern::Colorpoint, dominant: false });
+    assert_eq!(traits.eye_shape, Gene { expressed: EyeShape::Slanted, hidden: EyeShape::Wide, dominant: true });
+    assert_eq!(traits.rarity, Gene { expressed: RarityTier::Common, hidden: RarityTier::Legendary, dominant: false });
+
+    dna[6] = 0xfe;
+    assert_eq!(genes::KittyTraits::from_dna(&dna).rarity.expressed, RarityTier::Legendary);
+}
+
+#[test]
+fn combine_dna_inherits_one_allele_from_each_parent() {
+    let dna1 = [0x81, 0x02, 0x83, 0x04, 0x85, 0x06, 0x87, 0x08, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff];
+    let dna2 = [0x11, 0x91, 0x13, 0x94, 0x15, 0x96, 0x17, 0x98, 0, 0, 0, 0, 0, 0, 0, 0];
+
+    let child = genes::combine_dna(&dna1, &dna2, &[0u8; 16]);
+    assert_eq!(child[..8], [0x81, 0x11, 0x83, 0x13, 0x85, 0x15, 0x87, 0x17]);
+    assert_eq!(child[8..], [0u8; 8]);
+
+    let child = genes::combine_dna(&dna1, &dna2, &[0x01; 16]);
+    assert_eq!(child[..8], [0x02, 0x91, 0x04, 0x94, 0x06, 0x96, 0x08, 0x98]);
+    assert_eq!(child[8..], [0x01; 8]);
+
+    // 从父母分别继承隐性的 Ginger 和显性的 White, 表达出来的是 White
+    let traits = genes::KittyTraits::from_dna(&child);
+    assert_eq!(traits.color, Gene { expressed: Color::White, hidden: Color::Ginger, dominant: true });
+}
+
+#[test]
+fn bred_kitty_inherits_parent_alleles() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+
+        let dna1 = Kitties::kitties(0).unwrap().dna;
+        let dna2 = Kitties::kitties(1).unwrap().dna;
+        let child = Kitties::kitties(2).unwrap().dna;
+
+        for pair in 0..genes::GENE_PAIRS {
+            let i = pair * 2;
+            assert!(child[i] == dna1[i] || child[i] == dna1[i + 1]);
+            assert!(child[i + 1] == dna2[i] || child[i + 1] == dna2[i + 1]);
+        }
+
+        assert_eq!(Kitties::kitty_traits(2), Some(genes::KittyTraits::from_dna(&child)));
+        assert_eq!(Kitties::kitty_traits(3), None);
+    })
+}
+
+#[test]
+fn mutate_respects_rate() {
+    let dna = [0x81, 0x02, 0x83, 0x04, 0x85, 0x06, 0x87, 0x08, 0, 0, 0, 0, 0, 0, 0, 0];
+    let mut entropy = [0u8; 32];
+    // 第 0 对突变第二个等位基因并改变显隐性, 其余各对突变第一个等位基因
+    entropy[16..24].copy_from_slice(&[1, 0x80, 0, 0, 0, 0x06, 0, 0]);
+
+    let mut mutated = dna;
+    assert_eq!(genes::mutate(&mut mutated, Perbill::zero(), &entropy), 0);
+    assert_eq!(mutated, dna);
+
+    let mut mutated = dna;
+    assert_eq!(genes::mutate(&mut mutated, Perbill::one(), &entropy), 4);
+    assert_eq!(mutated[..8], [0x81, 0x83, 0x82, 0x04, 0x82, 0x06, 0x86, 0x08]);
+    assert_eq!(mutated[8..], dna[8..]);
+
+    // 只有掷出的数小于突变率才会突变
+    entropy[..4].copy_from_slice(&10_000_000u32.to_le_bytes());
+    entropy[4..16].copy_from_slice(&[0xff; 12]);
+    let mut mutated = dna;
+    assert_eq!(genes::mutate(&mut mutated, Perbill::from_percent(2), &entropy), 1);
+    assert_eq!(mutated[..2], [0x81, 0x83]);
+}
+
+#[test]
+fn trait_counts_and_rarity_work() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+
+        let expressed: Vec<_> = (0..3).map(|id| Kitties::kitty_traits(id).unwrap().expressed()).collect();
+        for kind in 0..genes::GENE_PAIRS {
+            let total: u32 = TraitCounts::iter_prefix_values(kind as u8).sum();
+            assert_eq!(total, 3);
+            for traits in &expressed {
+                let (k, value) = traits[kind];
+                let count = expressed.iter().filter(|other| other[kind] == (k, value)).count() as u32;
+                assert_eq!(Kitties::trait_count(k, value), count);
+            }
+        }
+
+        for (id, traits) in expressed.iter().enumerate() {
+            let score: u32 = traits.iter().map(|(kind, value)| 300 / Kitties::trait_count(kind, value)).sum();
+            assert_eq!(Kitties::rarity_of(id as u32), Some(score));
+        }
+        assert_eq!(Kitties::rarity_of(3), None);
+    })
+}
+
+#[test]
+fn breed_without_mutation_emits_no_rare_mutation() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+
+        assert!(!System::events().iter().any(|record| matches!(
+            record.event, TestEvent::kitties_event(Event::<Test>::RareMutation(..))
+        )));
+    })
+}
+
+#[test]
+fn breed_with_mutation_reports_unique_traits() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        set_mutation_rate(Perbill::one());
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+
+        for kind in 0..genes::GENE_PAIRS {
+            assert_eq!(TraitCounts::iter_prefix_values(kind as u8).sum::<u32>(), 3);
+        }
+
+        let unique = Kitties::kitty_traits(2).unwrap().expressed().iter()
+            .any(|(kind, value)| Kitties::trait_count(kind, value) == 1);
+        let event = TestEvent::kitties_event(Event::<Test>::RareMutation(1, 2, Kitties::rarity_of(2).unwrap()));
+        assert_eq!(System::events().iter().any(|record| record.event == event), unique);
+    })
+}
+
+#[test]
+fn migrate_to_v5_counts_traits() {
+    new_test_ext().without_checks().execute_with(|| {
+        let dna = [0x82, 0x01, 0x05, 0x01, 0x83, 0x82, 0x7f, 0x10, 0, 0, 0, 0, 0, 0, 0, 0];
+        crate::Kitties::<Test>::insert(0, Kitty { dna, generation: 0 });
+        crate::Kitties::<Test>::insert(1, Kitty { dna, generation: 0 });
+        StorageVersion::put(Releases::V4_0_0);
+
+        Kitties::on_runtime_upgrade();
+
+        for (kind, value) in genes::KittyTraits::from_dna(&dna).expressed().iter() {
+            assert_eq!(Kitties::trait_count(kind, value), 2);
+        }
+        assert_eq!(Kitties::rarity_of(0), Some(400));
+        assert_eq!(StorageVersion::get(), Releases::V5_0_0);
+    })
+}
+
+#[test]
+fn burn_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(10)));
+        assert_ok!(Kitties::set_name(Origin::signed(1), 0, b"tom".to_vec()));
+        assert_eq!(Balances::reserved_balance(1), NameDeposit::get());
+
+        assert_ok!(Kitties::burn(Origin::signed(1), 0));
+
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::Burned(1, 0)),
+        );
+        assert_eq!(Kitties::kitties(0), None);
+        assert_eq!(Kitties::kitty_owner(0), None);
+        assert_eq!(Kitties::kitty_price(0), None);
+        assert_eq!(Kitties::kitty_name(0), None);
+        assert_eq!(Kitties::kitty_info(0), None);
+        assert_eq!(Kitties::kitties_of(&1, 0).iter().map(|info| info.id).collect::<Vec<_>>(), vec![1]);
+        assert_eq!(Kitties::owned_kitties_count(1), 1);
+        assert_eq!(locked(1), vec![(KITTIES_ID, KittyDeposit::get())]);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        for kind in 0..genes::GENE_PAIRS {
+            assert_eq!(TraitCounts::iter_prefix_values(kind as u8).sum::<u32>(), 1);
+        }
+
+        // 编号不会被重复使用
+        assert_eq!(Kitties::kitties_count(), 2);
+
+        assert_ok!(Kitties::burn(Origin::signed(1), 1));
+        assert_eq!(locked(1), vec![]);
+    })
+}
+
+#[test]
+fn burn_fails() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        assert_noop!(Kitties::burn(Origin::signed(2), 0), Error::<Test>::RequireOwner);
+        assert_noop!(Kitties::burn(Origin::signed(1), 1), Error::<Test>::RequireOwner);
+
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 0, AuctionKind::English, 10, 10, 20));
+        assert_noop!(Kitties::burn(Origin::signed(1), 0), Error::<Test>::KittyInAuction);
+    })
+}
+
+#[test]
+fn set_name_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+
+        assert_noop!(Kitties::set_name(Origin::signed(2), 0, b"tom".to_vec()), Error::<Test>::RequireOwner);
+        assert_noop!(Kitties::set_name(Origin::signed(1), 0, b"too long!".to_vec()), Error::<Test>::NameTooLong);
+        assert_noop!(Kitties::set_name(Origin::signed(1), 0, vec![]), Error::<Test>::NameNotSet);
+
+        assert_ok!(Kitties::set_name(Origin::signed(1), 0, b"tom".to_vec()));
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::NameSet(1, 0, b"tom".to_vec())),
+        );
+        assert_eq!(Kitties::kitty_info(0).unwrap().name, Some(b"tom".to_vec()));
+        assert_eq!(Balances::reserved_balance(1), 3);
+
+        // 同一个账户改名不需要再次质押
+        assert_ok!(Kitties::set_name(Origin::signed(1), 0, b"kitty".to_vec()));
+        assert_eq!(Kitties::kitty_name(0), Some((b"kitty".to_vec(), 1, 3)));
+        assert_eq!(Balances::reserved_balance(1), 3);
+
+        // 转让时名字被清除, 押金退还给起名的账户
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+        assert_eq!(Kitties::kitty_name(0), None);
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_ok!(Kitties::set_name(Origin::signed(2), 0, b"felix".to_vec()));
+        assert_eq!(Balances::reserved_balance(2), 3);
+
+        assert_ok!(Kitties::set_name(Origin::signed(2), 0, vec![]));
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::NameCleared(2, 0)),
+        );
+        assert_eq!(Kitties::kitty_name(0), None);
+        assert_eq!(Balances::reserved_balance(2), 0);
+    })
+}
+
+#[test]
+fn set_name_fails_without_deposit() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        // 余额只够锁定小猫的押金, 锁定的部分不能再质押
+        let _ = Balances::deposit_creating(&1, KittyDeposit::get() + 1);
+        let _ = Kitties::create(Origin::signed(1));
+
+        assert_noop!(Kitties::set_name(Origin::signed(1), 0, b"tom".to_vec()), Error::<Test>::BalanceNotEnough);
+    })
+}
+
+#[test]
+fn approve_and_transfer_from_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&3, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+
+        assert_ok!(Kitties::approve(Origin::signed(1), 0, Some(2)));
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::Approval(1, Some(2), 0)),
+        );
+        assert_eq!(Kitties::kitty_approval(0), Some(2));
+        assert!(Kitties::is_approved_or_owner(&2, 0));
+
+        assert_noop!(Kitties::transfer_from(Origin::signed(2), 3, 2, 0), Error::<Test>::WrongOwner);
+        assert_noop!(Kitties::transfer_from(Origin::signed(3), 1, 3, 0), Error::<Test>::RequireOwner);
+
+        assert_ok!(Kitties::transfer_from(Origin::signed(2), 1, 3, 0));
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::Transferred(1, 3, 0)),
+        );
+        assert_eq!(Kitties::kitty_owner(0), Some(3));
+
+        // 转让之后授权被清除
+        assert_eq!(Kitties::kitty_approval(0), None);
+        assert!(!Kitties::is_approved_or_owner(&2, 0));
+        assert_noop!(Kitties::transfer(Origin::signed(2), 1, 0), Error::<Test>::RequireOwner);
+    })
+}
+
+#[test]
+fn approve_fails() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+
+        assert_noop!(Kitties::approve(Origin::signed(2), 0, Some(2)), Error::<Test>::RequireOwner);
+        assert_noop!(Kitties::approve(Origin::signed(1), 1, Some(2)), Error::<Test>::RequireOwner);
+        assert_noop!(Kitties::approve(Origin::signed(1), 0, Some(1)), Error::<Test>::ApproveToOwner);
+        assert_noop!(Kitties::set_approval_for_all(Origin::signed(1), 1, true), Error::<Test>::ApproveToOwner);
+
+        // 被授权的账户不能再授权给别人
+        assert_ok!(Kitties::approve(Origin::signed(1), 0, Some(2)));
+        assert_noop!(Kitties::approve(Origin::signed(2), 0, Some(3)), Error::<Test>::RequireOwner);
+
+        assert_ok!(Kitties::approve(Origin::signed(1), 0, None));
+        assert_eq!(Kitties::kitty_approval(0), None);
+    })
+}
+
+#[test]
+fn operator_can_manage_all_kitties() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&3, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+
+        assert_ok!(Kitties::set_approval_for_all(Origin::signed(1), 2, true));
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::ApprovalForAll(1, 2, true)),
+        );
+
+        // 代理可以转让
+        assert_ok!(Kitties::transfer(Origin::signed(2), 3, 0));
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::Transferred(1, 3, 0)),
+        );
+        assert_eq!(Kitties::kitty_owner(0), Some(3));
+
+        // 代理可以挂单, 货款支付给拥有者
+        assert_ok!(Kitties::ask(Origin::signed(2), 1, Some(10)));
+        assert_eq!(
+            last_event(),
+            TestEvent::kitties_event(Event::<Test>::Ask(1, 1, Some(Listing { price: 10, nonce: 0 }))),
+        );
+        let nonce = Kitties::kitty_price(1).unwrap().nonce;
+        assert_ok!(Kitties::buy(Origin::signed(3), 1, 10, nonce));
+        assert_eq!(Balances::free_balance(1), 100 + 10 - 1);
+
+        // 代理也可以给单只小猫授权
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::approve(Origin::signed(2), 2, Some(4)));
+        assert_eq!(Kitties::kitty_approval(2), Some(4));
+
+        assert_ok!(Kitties::set_approval_for_all(Origin::signed(1), 2, false));
+        assert!(!Kitties::is_approved_for_all(&1, &2));
+        assert_noop!(Kitties::ask(Origin::signed(2), 2, Some(10)), Error::<Test>::RequireOwner);
+    })
+}
+
+#[test]
+fn unique_assets_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::burn(Origin::signed(1), 1));
+
+        assert_eq!(<Kitties as UniqueAssets<u64>>::total_supply(), 2);
+        assert_eq!(<Kitties as UniqueAssets<u64>>::owner_of(0), Some(1));
+        assert_eq!(<Kitties as UniqueAssets<u64>>::owner_of(1), None);
+        assert_eq!(<Kitties as UniqueAssets<u64>>::assets_of(&1), vec![0, 2]);
+
+        assert_noop!(<Kitties as UniqueAssets<u64>>::transfer(&2, &3, 0), Error::<Test>::WrongOwner);
+        assert_noop!(<Kitties as UniqueAssets<u64>>::transfer(&1, &3, 0), Error::<Test>::BalanceNotEnough);
+
+        assert_ok!(<Kitties as UniqueAssets<u64>>::transfer(&1, &2, 0));
+        assert_eq!(<Kitties as UniqueAssets<u64>>::owner_of(0), Some(2));
+        assert_eq!(<Kitties as UniqueAssets<u64>>::assets_of(&1), vec![2]);
+        assert_eq!(<Kitties as UniqueAssets<u64>>::assets_of(&2), vec![0]);
+        assert_eq!(locked(2), vec![(KITTIES_ID, KittyDeposit::get())]);
+
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 2, AuctionKind::English, 10, 10, 20));
+        assert_noop!(<Kitties as UniqueAssets<u64>>::transfer(&1, &2, 2), Error::<Test>::KittyInAuction);
+    })
+}
+
+#[test]
+fn unique_assets_lock_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(10)));
+        assert_ok!(Kitties::make_offer(Origin::signed(2), 0, 10, 20));
+
+        assert_noop!(<Kitties as UniqueAssets<u64>>::lock(&2, 0), Error::<Test>::WrongOwner);
+        assert_ok!(<Kitties as UniqueAssets<u64>>::lock(&1, 0));
+        assert_eq!(last_event(), TestEvent::kitties_event(Event::<Test>::Locked(1, 0)));
+        assert_noop!(<Kitties as UniqueAssets<u64>>::lock(&1, 0), Error::<Test>::KittyLocked);
+
+        // 锁定期间拥有者不变, 押金仍然由拥有者锁定
+        assert!(<Kitties as UniqueAssets<u64>>::is_locked(0));
+        assert_eq!(<Kitties as UniqueAssets<u64>>::owner_of(0), Some(1));
+        assert_eq!(<Kitties as UniqueAssets<u64>>::assets_of(&1), vec![0, 1]);
+
+        // 锁定的小猫不能转移、出售、拍卖、出租或者销毁
+        assert_noop!(<Kitties as UniqueAssets<u64>>::transfer(&1, &2, 0), Error::<Test>::KittyLocked);
+        assert_noop!(Kitties::transfer(Origin::signed(1), 2, 0), Error::<Test>::KittyLocked);
+        assert_noop!(Kitties::buy(Origin::signed(2), 0, 10, 0), Error::<Test>::KittyLocked);
+        assert_noop!(Kitties::ask(Origin::signed(1), 0, None), Error::<Test>::KittyLocked);
+        assert_noop!(Kitties::accept_offer(Origin::signed(1), 0, 2), Error::<Test>::KittyLocked);
+        assert_noop!(
+            Kitties::create_auction(Origin::signed(1), 0, AuctionKind::English, 10, 10, 20),
+            Error::<Test>::KittyLocked
+        );
+        assert_noop!(Kitties::lease(Origin::signed(1), 0, 2, 20, 1), Error::<Test>::KittyLocked);
+        assert_noop!(Kitties::burn(Origin::signed(1), 0), Error::<Test>::KittyLocked);
+
+        // 锁定不影响繁殖
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+
+        // 拍卖中的小猫不能锁定
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 1, AuctionKind::English, 10, 10, 20));
+        assert_noop!(<Kitties as UniqueAssets<u64>>::lock(&1, 1), Error::<Test>::KittyInAuction);
+
+        assert_ok!(<Kitties as UniqueAssets<u64>>::unlock(0));
+        assert_eq!(last_event(), TestEvent::kitties_event(Event::<Test>::Unlocked(1, 0)));
+        assert_noop!(<Kitties as UniqueAssets<u64>>::unlock(0), Error::<Test>::KittyNotLocked);
+        assert!(!<Kitties as UniqueAssets<u64>>::is_locked(0));
+
+        assert_ok!(Kitties::accept_offer(Origin::signed(1), 0, 2));
+        assert_eq!(<Kitties as UniqueAssets<u64>>::owner_of(0), Some(2));
+    })
+}
+
+#[test]
+fn mock_unique_assets_works() {
+    MockUniqueAssets::reset();
+    MockUniqueAssets::mint(1, 0);
+    MockUniqueAssets::mint(1, 1);
+    MockUniqueAssets::mint(2, 2);
+
+    assert_eq!(MockUniqueAssets::total_supply(), 3);
+    assert_eq!(MockUniqueAssets::assets_of(&1), vec![0, 1]);
+
+    assert!(MockUniqueAssets::transfer(&2, &3, 0).is_err());
+    assert_ok!(MockUniqueAssets::transfer(&1, &3, 0));
+    assert_eq!(MockUniqueAssets::owner_of(0), Some(3));
+
+    MockUniqueAssets::burn(1);
+    assert_eq!(MockUniqueAssets::owner_of(1), None);
+    assert_eq!(MockUniqueAssets::total_supply(), 2);
+
+    assert!(MockUniqueAssets::lock(&1, 0).is_err());
+    assert_ok!(MockUniqueAssets::lock(&3, 0));
+    assert!(MockUniqueAssets::is_locked(0));
+    assert!(MockUniqueAssets::transfer(&3, &1, 0).is_err());
+    assert_ok!(MockUniqueAssets::unlock(0));
+    assert!(MockUniqueAssets::unlock(0).is_err());
+    assert_ok!(MockUniqueAssets::transfer(&3, &1, 0));
+}
+
+// 可复现的伪随机数
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, bound: u32) -> u32 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % u64::from(bound)) as u32
+    }
+
+    // 从模型中随机选一项, 偶尔选一个不在链表中的值
+    fn pick(&mut self, model: &VecDeque<u32>) -> u32 {
+        if model.is_empty() || self.below(5) == 0 {
+            100 + self.below(4)
+        } else {
+            model[self.below(model.len() as u32) as usize]
+        }
+    }
+}
+
+fn assert_list_matches(key: u64, model: &VecDeque<u32>) {
+    let expected: Vec<u32> = model.iter().copied().collect();
+    assert_eq!(OwnedKittiesList::<Test>::iter(&key).collect::<Vec<_>>(), expected);
+    assert_eq!(OwnedKittiesList::<Test>::len(&key), model.len() as u32);
+    assert_eq!(OwnedKittiesList::<Test>::is_empty(&key), model.is_empty());
+
+    // 反向的链接也要正确
+    let mut backwards = Vec::new();
+    let mut cursor = Kitties::owned_kitties((key, None)).and_then(|head| head.prev);
+    while let Some(value) = cursor {
+        backwards.push(value);
+        cursor = Kitties::owned_kitties((key, Some(value))).and_then(|item| item.prev);
+    }
+    backwards.reverse();
+    assert_eq!(backwards, expected);
+}
+
+#[test]
+fn linked_list_matches_vec_deque_model() {
+    for seed in 0..32 {
+        new_test_ext().without_checks().execute_with(|| {
+            let mut rng = Rng(seed);
+            let mut models = [VecDeque::new(), VecDeque::new()];
+
+            for _ in 0..200 {
+                let key = rng.below(2) as u64;
+                let model = &mut models[key as usize];
+                let value = rng.below(16);
+
+                match rng.below(7) {
+                    0 | 1 => {
+                        // `append` 要求调用者保证值不重复
+                        if !model.contains(&value) {
+                            OwnedKittiesList::<Test>::append(&key, value);
+                            model.push_back(value);
+                        }
+                    }
+                    2 => {
+                        OwnedKittiesList::<Test>::remove(&key, value);
+                        model.retain(|v| *v != value);
+                    }
+                    3 => {
+                        let anchor = rng.pick(model);
+                        let position = model.iter().position(|v| *v == anchor);
+                        let expected = position.is_some() && !model.contains(&value);
+                        assert_eq!(OwnedKittiesList::<Test>::insert_after(&key, anchor, value), expected);
+                        if expected {
+                            model.insert(position.unwrap() + 1, value);
+                        }
+                    }
+                    4 => {
+                        let anchor = rng.pick(model);
+                        let position = model.iter().position(|v| *v == anchor);
+                        let expected = position.is_some() && !model.contains(&value);
+                        assert_eq!(OwnedKittiesList::<Test>::insert_before(&key, anchor, value), expected);
+                        if expected {
+                            model.insert(position.unwrap(), value);
+                        }
+                    }
+                    5 => {
+                        let after = if rng.below(3) == 0 { None } else { Some(rng.pick(model)) };
+                        let limit = rng.below(6);
+                        let expected: Vec<u32> = match after {
+                            None => model.iter().copied().take(limit as usize).collect(),
+                            Some(after) => match model.iter().position(|v| *v == after) {
+                                Some(position) => model.iter().copied().skip(position + 1).take(limit as usize).collect(),
+                                None => Vec::new(),
+                            },
+                        };
+                        assert_eq!(OwnedKittiesList::<Test>::page(&key, after, limit), expected);
+                    }
+                    _ => {
+                        if rng.below(4) == 0 {
+                            let expected: Vec<u32> = model.drain(..).collect();
+                            assert_eq!(OwnedKittiesList::<Test>::take_all(&key), expected);
+                            assert_eq!(Kitties::owned_kitties((key, None)), None);
+                        }
+                    }
+                }
+
+                assert_list_matches(0, &models[0]);
+                assert_list_matches(1, &models[1]);
+            }
+        });
+    }
+}
+
+#[test]
+fn kitties_of_after_pages_by_cursor() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        for _ in 0..5 {
+            let _ = Kitties::create(Origin::signed(1));
+        }
+        assert_eq!(Kitties::owned_kitties_count(1), 5);
+
+        let ids = |page: Vec<crate::KittyInfo<u64, u32, u64>>| page.into_iter().map(|info| info.id).collect::<Vec<_>>();
+        assert_eq!(ids(Kitties::kitties_of_after(&1, None, 2)), vec![0, 1]);
+        assert_eq!(ids(Kitties::kitties_of_after(&1, Some(1), 2)), vec![2, 3]);
+
+        // 翻页期间移除已经读过的小猫不影响下一页
+        assert_ok!(Kitties::burn(Origin::signed(1), 0));
+        assert_eq!(ids(Kitties::kitties_of_after(&1, Some(3), 2)), vec![4]);
+        assert_eq!(ids(Kitties::kitties_of_after(&1, Some(0), 2)), Vec::<u32>::new());
+        assert_eq!(Kitties::owned_kitties_count(1), 4);
+    })
+}
+
+#[test]
+fn try_state_accepts_normal_operations() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Balances::deposit_creating(&2, 100);
+
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        let _ = Kitties::create(Origin::signed(1));
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 1));
+        assert_ok!(Kitties::ask(Origin::signed(1), 2, Some(10)));
+        assert_ok!(Kitties::approve(Origin::signed(1), 3, Some(2)));
+        assert_ok!(Kitties::set_name(Origin::signed(2), 1, b"tom".to_vec()));
+        assert_ok!(Kitties::burn(Origin::signed(1), 0));
+
+        assert_ok!(Kitties::do_try_state());
+    })
+}
+
+#[test]
+fn try_state_detects_broken_lists() {
+    new_test_ext().without_checks().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        for _ in 0..3 {
+            let _ = Kitties::create(Origin::signed(1));
+        }
+
+        // 最后一只小猫指回第一只, 形成环
+        OwnedKitties::<Test>::mutate((1, Some(2)), |item| item.as_mut().unwrap().next = Some(0));
+        assert_eq!(Kitties::do_try_state(), Err("owned kitties list has a cycle"));
+        OwnedKitties::<Test>::mutate((1, Some(2)), |item| item.as_mut().unwrap().next = None);
+
+        OwnedKitties::<Test>::mutate((1, Some(1)), |item| item.as_mut().unwrap().prev = None);
+        assert_eq!(Kitties::do_try_state(), Err("owned kitties list prev does not match next"));
+        OwnedKitties::<Test>::mutate((1, Some(1)), |item| item.as_mut().unwrap().prev = Some(0));
+
+        crate::OwnedKittiesCount::<Test>::insert(1, 2);
+        assert_eq!(Kitties::do_try_state(), Err("OwnedKittiesCount does not match the list"));
+        crate::OwnedKittiesCount::<Test>::insert(1, 3);
+
+        KittyOwners::<Test>::insert(1, 2);
+        assert_eq!(Kitties::do_try_state(), Err("kitty is missing from its owner's list"));
+        KittyOwners::<Test>::insert(1, 1);
+
+        assert_ok!(Kitties::do_try_state());
+    })
+}
+
+#[test]
+fn try_state_detects_dangling_records() {
+    new_test_ext().without_checks().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 100);
+        let _ = Kitties::create(Origin::signed(1));
+
+        KittyPrices::<Test>::insert(5, Listing { price: 10, nonce: 0 });
+        assert_eq!(Kitties::do_try_state(), Err("price recorded for a missing kitty"));
+        KittyPrices::<Test>::remove(5);
+
+        KittyOwners::<Test>::insert(5, 1);
+        assert_eq!(Kitties::do_try_state(), Err("owner recorded for a missing kitty"));
+        KittyOwners::<Test>::remove(5);
+
+        KittiesCount::<Test>::put(0);
+        assert_eq!(Kitties::do_try_state(), Err("kitty id is not below KittiesCount"));
+        KittiesCount::<Test>::put(1);
+
+        TraitCounts::insert(0, 0, 5);
+        assert_eq!(Kitties::do_try_state(), Err("TraitCounts does not match Kitties"));
+    })
+}
+
+#[test]
+fn genesis_kitties_work() {
+    let kitties = vec![(1, [0x81; 16], None), (2, [0x02; 16], Some(5)), (1, [0x03; 16], None)];
+    new_test_ext_with_genesis(vec![(1, 10), (2, 10), (3, 10)], kitties).execute_with(|| {
+        assert_eq!(Kitties::kitties_count(), 3);
+        assert_eq!(Kitties::kitties(1), Some(Kitty { dna: [0x02; 16], generation: 0 }));
+        assert_eq!(Kitties::owned_kitty_ids(&1), vec![0, 2]);
+        assert_eq!(Kitties::kitty_creator(2).map(|(creator, _)| creator), Some(1));
+        assert_eq!(Kitties::population(), 3);
+
+        // 创世小猫和创建的小猫一样锁定押金
+        assert_eq!(locked(1), vec![(KITTIES_ID, 4)]);
+        assert_eq!(locked(2), vec![(KITTIES_ID, 2)]);
+
+        assert_eq!(Kitties::kitty_price(0), None);
+        assert_eq!(Kitties::kitty_price(1), Some(Listing { price: 5, nonce: 0 }));
+        assert_ok!(Kitties::buy(Origin::signed(3), 1, 5, 0));
+        assert_eq!(Kitties::kitty_owner(1), Some(3));
+
+        // 创世之后的编号接着往下排
+        assert_ok!(Kitties::create(Origin::signed(2)));
+        assert_eq!(Kitties::kitty_owner(3), Some(2));
+    })
+}
+
+#[test]
+#[should_panic(expected = "genesis kitty owner cannot afford the deposit")]
+fn genesis_kitties_require_deposit() {
+    new_test_ext_with_genesis(vec![(1, 3)], vec![(1, [0; 16], None), (1, [1; 16], None)]);
+}
+
+#[test]
+fn breed_with_sire_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 20);
+        let _ = Balances::deposit_creating(&2, 20);
+
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create(Origin::signed(2)));
+
+        assert_ok!(Kitties::list_sire(Origin::signed(1), 0, 5, None));
+        assert_eq!(Kitties::sire_listing(0), Some(SireListing { fee: 5, restricted: false }));
+        assert_eq!(last_event(), TestEvent::kitties_event(Event::<Test>::SireListed(1, 0, 5, false)));
+
+        assert_ok!(Kitties::breed_with_sire(Origin::signed(2), 1, 0, 6));
+        assert_eq!(last_event(), TestEvent::kitties_event(Event::<Test>::BredWithSire(2, 2, 0, 5)));
+
+        // 新小猫属于繁殖者, 配种费支付给种猫的拥有者
+        assert_eq!(Kitties::kitty_owner(2), Some(2));
+        assert_eq!(Kitties::kitty_owner(0), Some(1));
+        assert_eq!(Balances::free_balance(1), 25);
+        assert_eq!(Balances::free_balance(2), 15);
+        assert_eq!(locked(2), vec![(KITTIES_ID, 4)]);
+
+        // 血统和冷却和 `breed` 一样记录
+        assert_eq!(Kitties::kitty_parents(2), (1, 0));
+        assert_eq!(Kitties::kitty_children(0), vec![2]);
+        assert_eq!(Kitties::next_breeding(0), 15);
+        assert_eq!(Kitties::next_breeding(1), 15);
+
+        // 挂单保留, 冷却结束之后可以再次使用
+        assert_noop!(Kitties::breed_with_sire(Origin::signed(2), 1, 0, 5), Error::<Test>::KittyOnCooldown);
+        run_to_block(15);
+        assert_ok!(Kitties::breed_with_sire(Origin::signed(2), 1, 0, 5));
+        assert_eq!(Kitties::kitty_owner(3), Some(2));
+    })
+}
+
+#[test]
+fn breed_with_sire_fails() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 20);
+        let _ = Balances::deposit_creating(&2, 20);
+        let _ = Balances::deposit_creating(&3, 5);
+
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create(Origin::signed(2)));
+        assert_ok!(Kitties::create(Origin::signed(3)));
+
+        assert_noop!(Kitties::breed_with_sire(Origin::signed(2), 1, 0, 5), Error::<Test>::NotSireListed);
+        assert_noop!(Kitties::breed_with_sire(Origin::signed(2), 1, 4, 5), Error::<Test>::InvalidKittyId);
+        assert_noop!(Kitties::list_sire(Origin::signed(2), 0, 5, None), Error::<Test>::RequireOwner);
+
+        assert_ok!(Kitties::list_sire(Origin::signed(1), 0, 5, None));
+        assert_noop!(Kitties::breed_with_sire(Origin::signed(2), 1, 0, 4), Error::<Test>::SireFeeTooHigh);
+        assert_noop!(Kitties::breed_with_sire(Origin::signed(2), 2, 0, 5), Error::<Test>::RequireOwner);
+        assert_noop!(Kitties::breed_with_sire(Origin::signed(2), 0, 0, 5), Error::<Test>::RequireOwner);
+        // 账户 3 付不起配种费和新小猫的押金
+        assert_noop!(Kitties::breed_with_sire(Origin::signed(3), 2, 0, 5), Error::<Test>::BalanceNotEnough);
+
+        assert_noop!(Kitties::unlist_sire(Origin::signed(2), 0), Error::<Test>::RequireOwner);
+        assert_noop!(Kitties::unlist_sire(Origin::signed(2), 1), Error::<Test>::NotSireListed);
+        assert_ok!(Kitties::unlist_sire(Origin::signed(1), 0));
+        assert_eq!(last_event(), TestEvent::kitties_event(Event::<Test>::SireUnlisted(1, 0)));
+        assert_noop!(Kitties::breed_with_sire(Origin::signed(2), 1, 0, 5), Error::<Test>::NotSireListed);
+    })
+}
+
+#[test]
+fn sire_whitelist_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        for who in 1..=4 {
+            let _ = Balances::deposit_creating(&who, 20);
+            assert_ok!(Kitties::create(Origin::signed(who)));
+        }
+
+        assert_noop!(
+            Kitties::list_sire(Origin::signed(1), 0, 5, Some(vec![2, 3, 4, 5])),
+            Error::<Test>::TooManySireApprovals
+        );
+        assert_ok!(Kitties::list_sire(Origin::signed(1), 0, 5, Some(vec![2, 3])));
+        assert!(Kitties::is_sire_approved(0, 2));
+
+        assert_noop!(Kitties::breed_with_sire(Origin::signed(4), 3, 0, 5), Error::<Test>::NotOnSireWhitelist);
+        assert_ok!(Kitties::breed_with_sire(Origin::signed(2), 1, 0, 5));
+
+        // 重新挂出替换原来的白名单
+        run_to_block(15);
+        assert_ok!(Kitties::list_sire(Origin::signed(1), 0, 5, Some(vec![4])));
+        assert!(!Kitties::is_sire_approved(0, 2));
+        assert_noop!(Kitties::breed_with_sire(Origin::signed(3), 2, 0, 5), Error::<Test>::NotOnSireWhitelist);
+        assert_ok!(Kitties::breed_with_sire(Origin::signed(4), 3, 0, 5));
+
+        // 取消白名单之后任何人都可以使用
+        run_to_block(20);
+        assert_ok!(Kitties::list_sire(Origin::signed(1), 0, 5, None));
+        assert!(!Kitties::is_sire_approved(0, 4));
+        assert_ok!(Kitties::breed_with_sire(Origin::signed(3), 2, 0, 5));
+    })
+}
+
+#[test]
+fn sire_listing_is_cleared_on_transfer_burn_and_auction() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 20);
+        let _ = Balances::deposit_creating(&2, 20);
+
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create(Origin::signed(1)));
+
+        assert_ok!(Kitties::list_sire(Origin::signed(1), 0, 5, Some(vec![2])));
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+        assert_eq!(Kitties::sire_listing(0), None);
+        assert!(!Kitties::is_sire_approved(0, 2));
+
+        assert_ok!(Kitties::list_sire(Origin::signed(1), 1, 5, Some(vec![2])));
+        assert_ok!(Kitties::burn(Origin::signed(1), 1));
+        assert_eq!(Kitties::sire_listing(1), None);
+        assert!(!Kitties::is_sire_approved(1, 2));
+
+        // 拍卖会取消种猫挂单, 拍卖期间不能再挂出
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::list_sire(Origin::signed(1), 2, 5, Some(vec![2])));
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 2, AuctionKind::English, 10, 20, 20));
+        assert_eq!(Kitties::sire_listing(2), None);
+        assert!(!Kitties::is_sire_approved(2, 2));
+        assert_noop!(Kitties::list_sire(Origin::signed(1), 2, 5, None), Error::<Test>::KittyInAuction);
+    })
+}
+
+#[test]
+fn lease_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 20);
+        let _ = Balances::deposit_creating(&2, 20);
+
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create(Origin::signed(2)));
+        assert_ok!(Kitties::ask(Origin::signed(1), 0, Some(5)));
+        assert_ok!(Kitties::list_sire(Origin::signed(1), 0, 5, None));
+
+        assert_ok!(Kitties::lease(Origin::signed(1), 0, 2, 20, 10));
+        assert_eq!(Kitties::lease_offer(0), Some(LeaseTerms { lessee: 2, fee: 10, until: 20 }));
+        assert_eq!(last_event(), TestEvent::kitties_event(Event::<Test>::LeaseOffered(1, 2, 0, 20, 10)));
+
+        assert_ok!(Kitties::accept_lease(Origin::signed(2), 0, 10));
+        assert_eq!(last_event(), TestEvent::kitties_event(Event::<Test>::LeaseStarted(1, 2, 0, 20, 10)));
+        assert_eq!(Kitties::active_lease(0), Some(Lease { lessee: 2, fee: 10, start: 10, until: 20 }));
+        assert_eq!(Kitties::lease_offer(0), None);
+        assert_eq!(Balances::reserved_balance(2), 10);
+
+        // 承租人是使用者, 拥有者不变, 挂单被取消
+        assert_eq!(Kitties::kitty_user(0), Some(2));
+        assert_eq!(Kitties::kitty_owner(0), Some(1));
+        assert_eq!(Kitties::kitty_info(0).unwrap().lessee, Some(2));
+        assert_eq!(Kitties::kitty_price(0), None);
+        assert_eq!(Kitties::sire_listing(0), None);
+
+        // 租约期间拥有者不能使用、转让或者出售, 承租人也不能转让
+        assert_noop!(Kitties::breed(Origin::signed(1), 0, 1), Error::<Test>::KittyLeased);
+        assert_noop!(Kitties::transfer(Origin::signed(1), 3, 0), Error::<Test>::KittyLeased);
+        assert_noop!(Kitties::ask(Origin::signed(1), 0, Some(5)), Error::<Test>::KittyLeased);
+        assert_noop!(
+            Kitties::create_auction(Origin::signed(1), 0, AuctionKind::English, 10, 20, 20),
+            Error::<Test>::KittyLeased
+        );
+        assert_noop!(Kitties::list_sire(Origin::signed(1), 0, 5, None), Error::<Test>::KittyLeased);
+        assert_noop!(Kitties::burn(Origin::signed(1), 0), Error::<Test>::KittyLeased);
+        assert_noop!(Kitties::lease(Origin::signed(1), 0, 3, 30, 10), Error::<Test>::KittyLeased);
+        assert_noop!(Kitties::transfer(Origin::signed(2), 3, 0), Error::<Test>::RequireOwner);
+        assert_noop!(Kitties::ask(Origin::signed(2), 0, Some(5)), Error::<Test>::RequireOwner);
+
+        // 承租人用租来的小猫繁殖, 新小猫属于承租人
+        assert_ok!(Kitties::breed(Origin::signed(2), 0, 2));
+        assert_eq!(Kitties::kitty_owner(3), Some(2));
+
+        // 租约到期, 租金支付给拥有者
+        run_to_block(19);
+        assert_eq!(Kitties::kitty_user(0), Some(2));
+        run_to_block(20);
+        assert_eq!(Kitties::kitty_user(0), Some(1));
+        assert_eq!(Kitties::active_lease(0), None);
+        assert_eq!(last_event(), TestEvent::kitties_event(Event::<Test>::LeaseEnded(1, 2, 0, 10)));
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Balances::free_balance(2), 10);
+        assert_eq!(Balances::free_balance(1), 30);
+
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+    })
+}
+
+#[test]
+fn terminate_lease_refunds_unused_fee() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 20);
+        let _ = Balances::deposit_creating(&2, 20);
+
+        assert_ok!(Kitties::create(Origin::signed(1)));
+
+        assert_noop!(Kitties::terminate_lease(Origin::signed(1), 0), Error::<Test>::LeaseNotExist);
+
+        // 还没有被接受的条件可以直接撤回
+        assert_ok!(Kitties::lease(Origin::signed(1), 0, 2, 20, 10));
+        assert_ok!(Kitties::terminate_lease(Origin::signed(1), 0));
+        assert_eq!(last_event(), TestEvent::kitties_event(Event::<Test>::LeaseOfferCancelled(1, 0)));
+        assert_noop!(Kitties::accept_lease(Origin::signed(2), 0, 10), Error::<Test>::LeaseOfferNotExist);
+
+        assert_ok!(Kitties::lease(Origin::signed(1), 0, 2, 20, 10));
+        assert_ok!(Kitties::accept_lease(Origin::signed(2), 0, 10));
+        assert_noop!(Kitties::terminate_lease(Origin::signed(2), 0), Error::<Test>::RequireOwner);
+
+        // 10 个区块的租约过去了 4 个, 拥有者得到 4, 承租人退还 6
+        run_to_block(14);
+        assert_eq!(Kitties::split_lease_fee(&Kitties::active_lease(0).unwrap(), 14), (4, 6));
+        assert_ok!(Kitties::terminate_lease(Origin::signed(1), 0));
+        assert_eq!(last_event(), TestEvent::kitties_event(Event::<Test>::LeaseTerminated(1, 2, 0, 4, 6)));
+        assert_eq!(Kitties::kitty_user(0), Some(1));
+        assert!(Kitties::leases_ending(20).is_empty());
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Balances::free_balance(2), 16);
+        assert_eq!(Balances::free_balance(1), 24);
+
+        // 到期的区块什么都不会发生
+        run_to_block(20);
+        assert_eq!(Balances::free_balance(1), 24);
+    })
+}
+
+#[test]
+fn lease_fails() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+
+        let _ = Balances::deposit_creating(&1, 20);
+        let _ = Balances::deposit_creating(&2, 20);
+        let _ = Balances::deposit_creating(&3, 5);
+
+        for _ in 0..4 {
+            assert_ok!(Kitties::create(Origin::signed(1)));
+        }
+
+        assert_noop!(Kitties::lease(Origin::signed(2), 0, 3, 20, 10), Error::<Test>::RequireOwner);
+        assert_noop!(Kitties::lease(Origin::signed(1), 0, 1, 20, 10), Error::<Test>::LeaseToOwner);
+        assert_noop!(Kitties::lease(Origin::signed(1), 0, 2, 10, 10), Error::<Test>::InvalidLeaseEnd);
+
+        assert_ok!(Kitties::lease(Origin::signed(1), 0, 3, 20, 10));
+        assert_noop!(Kitties::accept_lease(Origin::signed(2), 0, 10), Error::<Test>::LeaseOfferNotExist);
+        assert_noop!(Kitties::accept_lease(Origin::signed(3), 0, 9), Error::<Test>::LeaseFeeTooHigh);
+        assert_noop!(Kitties::accept_lease(Origin::signed(3), 0, 10), Error::<Test>::BalanceNotEnough);
+
+        // 出租条件过期之后不能再接受
+        assert_ok!(Kitties::lease(Origin::signed(1), 1, 2, 12, 1));
+        run_to_block(12);
+        assert_noop!(Kitties::accept_lease(Origin::signed(2), 1, 1), Error::<Test>::InvalidLeaseEnd);
+
+        // 拍卖中的小猫不能出租
+        assert_ok!(Kitties::lease(Origin::signed(1), 2, 2, 20, 1));
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 2, AuctionKind::English, 10, 20, 20));
+        assert_noop!(Kitties::accept_lease(Origin::signed(2), 2, 1), Error::<Test>::KittyInAuction);
+        assert_noop!(Kitties::lease(Origin::signed(1), 2, 2, 20, 1), Error::<Test>::KittyInAuction);
+
+        // 同一个区块结束的租约有上限
+        assert_ok!(Kitties::lease(Origin::signed(1), 1, 2, 20, 1));
+        assert_ok!(Kitties::lease(Origin::signed(1), 3, 2, 20, 1));
+        assert_ok!(Kitties::lease(Origin::signed(1), 0, 2, 20, 1));
+        assert_ok!(Kitties::accept_lease(Origin::signed(2), 1, 1));
+        assert_ok!(Kitties::accept_lease(Origin::signed(2), 3, 1));
+        assert_noop!(Kitties::accept_lease(Origin::signed(2), 0, 1), Error::<Test>::TooManyLeases);
+
+        // 转让小猫会清除出租条件
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+        assert_eq!(Kitties::lease_offer(0), None);
+    })
+}
@@ -0,0 +1,370 @@
+//! Weights for pallet_kitties.
+//!
+//! The values are estimates from the storage accesses of each call and have not been measured.
+//! Replace this file with the benchmark output from reference hardware, unedited:
+//!
+//! ./target/release/node-template benchmark --chain dev --execution wasm --wasm-execution compiled
+//!     --pallet pallet_kitties --extrinsic '*' --steps 50 --repeat 20 --output pallets/kitties/src/weights.rs
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{Weight, constants::RocksDbWeight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_kitties.
+pub trait WeightInfo {
+    fn create() -> Weight;
+    fn breed(a: u32, ) -> Weight;
+    fn transfer(w: u32, ) -> Weight;
+    fn ask() -> Weight;
+    fn buy(w: u32, ) -> Weight;
+    fn create_auction(w: u32, ) -> Weight;
+    fn bid() -> Weight;
+    fn cancel_auction() -> Weight;
+    fn on_initialize(a: u32, ) -> Weight;
+    fn make_offer() -> Weight;
+    fn accept_offer(w: u32, ) -> Weight;
+    fn withdraw_offer() -> Weight;
+    fn expire_offers(o: u32, ) -> Weight;
+    fn burn(w: u32, ) -> Weight;
+    fn set_name() -> Weight;
+    fn transfer_from(w: u32, ) -> Weight;
+    fn approve() -> Weight;
+    fn set_approval_for_all() -> Weight;
+    fn list_sire(w: u32, ) -> Weight;
+    fn unlist_sire(w: u32, ) -> Weight;
+    fn breed_with_sire(a: u32, ) -> Weight;
+    fn lease() -> Weight;
+    fn accept_lease(w: u32, ) -> Weight;
+    fn terminate_lease() -> Weight;
+    fn expire_leases(l: u32, ) -> Weight;
+    fn sweep_legacy_locks(n: u32, ) -> Weight;
+}
+
+/// Weights for pallet_kitties using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+    fn create() -> Weight {
+        (70_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+    }
+    fn breed(a: u32, ) -> Weight {
+        (110_000_000 as Weight)
+            .saturating_add((1_000_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(T::DbWeight::get().reads(16 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(a as Weight)))
+            .saturating_add(T::DbWeight::get().writes(16 as Weight))
+    }
+    fn transfer(w: u32, ) -> Weight {
+        (90_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn ask() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn buy(w: u32, ) -> Weight {
+        (120_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(13 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn create_auction(w: u32, ) -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn bid() -> Weight {
+        (105_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
+            .saturating_add(T::DbWeight::get().writes(11 as Weight))
+    }
+    fn cancel_auction() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    fn on_initialize(a: u32, ) -> Weight {
+        (5_000_000 as Weight)
+            .saturating_add((95_000_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads((10 as Weight).saturating_mul(a as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((11 as Weight).saturating_mul(a as Weight)))
+    }
+    fn make_offer() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn accept_offer(w: u32, ) -> Weight {
+        (110_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(T::DbWeight::get().reads(13 as Weight))
+            .saturating_add(T::DbWeight::get().writes(14 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn withdraw_offer() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn expire_offers(o: u32, ) -> Weight {
+        (0 as Weight)
+            .saturating_add((40_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn burn(w: u32, ) -> Weight {
+        (85_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(T::DbWeight::get().reads(14 as Weight))
+            .saturating_add(T::DbWeight::get().writes(17 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn set_name() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn transfer_from(w: u32, ) -> Weight {
+        (95_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn approve() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn set_approval_for_all() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn list_sire(w: u32, ) -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add((5_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(w as Weight)))
+    }
+    fn unlist_sire(w: u32, ) -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn breed_with_sire(a: u32, ) -> Weight {
+        (150_000_000 as Weight)
+            .saturating_add((1_000_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(T::DbWeight::get().reads(19 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(a as Weight)))
+            .saturating_add(T::DbWeight::get().writes(17 as Weight))
+    }
+    fn lease() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    fn accept_lease(w: u32, ) -> Weight {
+        (65_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn terminate_lease() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    fn expire_leases(l: u32, ) -> Weight {
+        (0 as Weight)
+            .saturating_add((45_000_000 as Weight).saturating_mul(l as Weight))
+            .saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(l as Weight)))
+            .saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(l as Weight)))
+    }
+    fn sweep_legacy_locks(n: u32, ) -> Weight {
+        (5_000_000 as Weight)
+            .saturating_add((15_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn create() -> Weight {
+        (70_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+    }
+    fn breed(a: u32, ) -> Weight {
+        (110_000_000 as Weight)
+            .saturating_add((1_000_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(RocksDbWeight::get().reads(16 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(a as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(16 as Weight))
+    }
+    fn transfer(w: u32, ) -> Weight {
+        (90_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn ask() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn buy(w: u32, ) -> Weight {
+        (120_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(13 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn create_auction(w: u32, ) -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn bid() -> Weight {
+        (105_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(11 as Weight))
+    }
+    fn cancel_auction() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    fn on_initialize(a: u32, ) -> Weight {
+        (5_000_000 as Weight)
+            .saturating_add((95_000_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((10 as Weight).saturating_mul(a as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((11 as Weight).saturating_mul(a as Weight)))
+    }
+    fn make_offer() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn accept_offer(w: u32, ) -> Weight {
+        (110_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(RocksDbWeight::get().reads(13 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(14 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn withdraw_offer() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn expire_offers(o: u32, ) -> Weight {
+        (0 as Weight)
+            .saturating_add((40_000_000 as Weight).saturating_mul(o as Weight))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(o as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(o as Weight)))
+    }
+    fn burn(w: u32, ) -> Weight {
+        (85_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(RocksDbWeight::get().reads(14 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(17 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn set_name() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn transfer_from(w: u32, ) -> Weight {
+        (95_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn approve() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn set_approval_for_all() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn list_sire(w: u32, ) -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add((5_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(w as Weight)))
+    }
+    fn unlist_sire(w: u32, ) -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn breed_with_sire(a: u32, ) -> Weight {
+        (150_000_000 as Weight)
+            .saturating_add((1_000_000 as Weight).saturating_mul(a as Weight))
+            .saturating_add(RocksDbWeight::get().reads(19 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(a as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(17 as Weight))
+    }
+    fn lease() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    fn accept_lease(w: u32, ) -> Weight {
+        (65_000_000 as Weight)
+            .saturating_add((2_000_000 as Weight).saturating_mul(w as Weight))
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(w as Weight)))
+    }
+    fn terminate_lease() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    fn expire_leases(l: u32, ) -> Weight {
+        (0 as Weight)
+            .saturating_add((45_000_000 as Weight).saturating_mul(l as Weight))
+            .saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(l as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(l as Weight)))
+    }
+    fn sweep_legacy_locks(n: u32, ) -> Weight {
+        (5_000_000 as Weight)
+            .saturating_add((15_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+    }
+}
@@ -0,0 +1,233 @@
+//! RPC interface for the proof-of-existence template pallet.
+
+use std::sync::Arc;
+
+use codec::{Codec, Decode};
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sc_client_api::{Backend, StorageProvider};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, storage::StorageKey, twox_128};
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, NumberFor}};
+
+pub use pallet_template_runtime_api::ProofOfExistenceApi as ProofOfExistenceRuntimeApi;
+
+/// A self-contained attestation that a claim was notarized.
+#[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attestation<AccountId, BlockNumber, BlockHash, Moment> {
+	/// The claim that was looked up.
+	pub claim: Bytes,
+	/// The current owner of the claim.
+	pub owner: AccountId,
+	/// The block in which the claim was recorded.
+	pub block_number: BlockNumber,
+	/// The hash of the block in which the claim was recorded.
+	pub block_hash: BlockHash,
+	/// The timestamp of the block in which the claim was recorded.
+	pub timestamp: Moment,
+}
+
+#[rpc]
+pub trait ProofOfExistenceApi<BlockHash, AccountId, BlockNumber, Moment> {
+	/// Verify a claim against `Proofs` storage.
+	///
+	/// `claim` is either the raw claim bytes, or the bytes of a hash that was notarized.
+	/// Returns `None` if the claim does not exist.
+	#[rpc(name = "poe_verify")]
+	fn verify(
+		&self,
+		claim: Bytes,
+		at: Option<BlockHash>,
+	) -> Result<Option<Attestation<AccountId, BlockNumber, BlockHash, Moment>>>;
+
+	/// Verify a document notarized with `create_claim_hashed`.
+	///
+	/// The document is digested with the runtime's claim hashing algorithm before lookup.
+	#[rpc(name = "poe_verifyDocument")]
+	fn verify_document(
+		&self,
+		document: Bytes,
+		at: Option<BlockHash>,
+	) -> Result<Option<Attestation<AccountId, BlockNumber, BlockHash, Moment>>>;
+
+	/// Verify a Merkle inclusion proof for `leaf` against an anchored `root`.
+	///
+	/// `proof` lists the sibling of each level from the leaf up to the root.
+	/// Returns the attestation of the root, or `None` if the proof does not verify.
+	#[rpc(name = "poe_verifyInclusion")]
+	fn verify_inclusion(
+		&self,
+		root: BlockHash,
+		leaf: BlockHash,
+		index: u32,
+		proof: Vec<BlockHash>,
+		at: Option<BlockHash>,
+	) -> Result<Option<Attestation<AccountId, BlockNumber, BlockHash, Moment>>>;
+}
+
+/// A struct that implements the [`ProofOfExistenceApi`].
+pub struct ProofOfExistence<C, B, BE> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<(B, BE)>,
+}
+
+impl<C, B, BE> ProofOfExistence<C, B, BE> {
+	/// Create new `ProofOfExistence` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		ProofOfExistence { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// The block of a claim could not be found.
+	BlockNotFound,
+	/// The timestamp of a claim's block could not be read from storage.
+	TimestampNotFound,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::BlockNotFound => 2,
+			Error::TimestampNotFound => 3,
+		}
+	}
+}
+
+fn rpc_error(error: Error, message: &str, e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(error.into()),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, BE, AccountId, Moment>
+	ProofOfExistenceApi<<Block as BlockT>::Hash, AccountId, NumberFor<Block>, Moment>
+	for ProofOfExistence<C, Block, BE>
+where
+	Block: BlockT,
+	BE: 'static + Backend<Block>,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + StorageProvider<Block, BE>,
+	C: Send + Sync,
+	C::Api: ProofOfExistenceRuntimeApi<Block, AccountId, NumberFor<Block>, <Block as BlockT>::Hash>,
+	AccountId: Codec,
+	Moment: Codec,
+{
+	fn verify(
+		&self,
+		claim: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<Attestation<AccountId, NumberFor<Block>, <Block as BlockT>::Hash, Moment>>> {
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		self.attest(&at, claim)
+	}
+
+	fn verify_document(
+		&self,
+		document: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<Attestation<AccountId, NumberFor<Block>, <Block as BlockT>::Hash, Moment>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let digest = api.hash_claim(&at, document.to_vec())
+			.map_err(|e| rpc_error(Error::RuntimeError, "Unable to hash document.", e))?;
+
+		self.attest(&at, Bytes(digest.as_ref().to_vec()))
+	}
+
+	fn verify_inclusion(
+		&self,
+		root: <Block as BlockT>::Hash,
+		leaf: <Block as BlockT>::Hash,
+		index: u32,
+		proof: Vec<<Block as BlockT>::Hash>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<Attestation<AccountId, NumberFor<Block>, <Block as BlockT>::Hash, Moment>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let included = api.verify_inclusion(&at, root, leaf, index, proof)
+			.map_err(|e| rpc_error(Error::RuntimeError, "Unable to verify inclusion proof.", e))?;
+
+		if !included {
+			return Ok(None);
+		}
+
+		self.attest(&at, Bytes(root.as_ref().to_vec()))
+	}
+}
+
+impl<C, Block, BE> ProofOfExistence<C, Block, BE>
+where
+	Block: BlockT,
+	BE: 'static + Backend<Block>,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + StorageProvider<Block, BE>,
+	C: Send + Sync,
+{
+	fn attest<AccountId, Moment>(
+		&self,
+		at: &BlockId<Block>,
+		claim: Bytes,
+	) -> Result<Option<Attestation<AccountId, NumberFor<Block>, <Block as BlockT>::Hash, Moment>>>
+	where
+		C::Api: ProofOfExistenceRuntimeApi<Block, AccountId, NumberFor<Block>, <Block as BlockT>::Hash>,
+		AccountId: Codec,
+		Moment: Decode,
+	{
+		let api = self.client.runtime_api();
+
+		let proof = api.proof(at, claim.to_vec())
+			.map_err(|e| rpc_error(Error::RuntimeError, "Unable to query proof.", e))?;
+
+		let (owner, block_number) = match proof {
+			Some(proof) => proof,
+			None => return Ok(None),
+		};
+
+		let block_hash = self.client.hash(block_number)
+			.map_err(|e| rpc_error(Error::BlockNotFound, "Unable to query claim block hash.", e))?
+			.ok_or_else(|| rpc_error(Error::BlockNotFound, "Claim block not found.", block_number))?;
+
+		// Read `pallet_timestamp::Now` from the state of the claim block itself rather than
+		// calling into the runtime, so that blocks from before any runtime upgrade still work.
+		let timestamp = self.client.storage(&BlockId::hash(block_hash), &timestamp_key())
+			.map_err(|e| rpc_error(Error::TimestampNotFound, "Unable to read claim block timestamp.", e))?
+			.ok_or_else(|| rpc_error(Error::TimestampNotFound, "Claim block has no timestamp.", block_hash))?;
+		let timestamp = Moment::decode(&mut &timestamp.0[..])
+			.map_err(|e| rpc_error(Error::TimestampNotFound, "Unable to decode claim block timestamp.", e))?;
+
+		Ok(Some(Attestation {
+			claim,
+			owner,
+			block_number,
+			block_hash,
+			timestamp,
+		}))
+	}
+}
+
+/// The storage key of `pallet_timestamp::Now`.
+fn timestamp_key() -> StorageKey {
+	let mut key = twox_128(b"Timestamp").to_vec();
+	key.extend_from_slice(&twox_128(b"Now"));
+	StorageKey(key)
+}
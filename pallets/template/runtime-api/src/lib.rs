@@ -0,0 +1,27 @@
+//! Runtime API definition for the proof-of-existence template pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::too_many_arguments)]
+// The `unnecessary_mut_passed` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait ProofOfExistenceApi<AccountId, BlockNumber, Hash> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+		Hash: Codec,
+	{
+		/// Get the owner and creation block of a claim, if it has been notarized.
+		fn proof(claim: Vec<u8>) -> Option<(AccountId, BlockNumber)>;
+
+		/// Digest a document with the hashing algorithm used for hashed claims.
+		fn hash_claim(document: Vec<u8>) -> Hash;
+
+		/// Verify that `leaf` is the `index`-th leaf of the anchored Merkle `root`.
+		fn verify_inclusion(root: Hash, leaf: Hash, index: u32, proof: Vec<Hash>) -> bool;
+	}
+}
@@ -0,0 +1,182 @@
+//! Benchmarks for the proof-of-existence template pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use codec::Encode;
+use frame_benchmarking::{benchmarks, account};
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, Zero};
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+fn funded_account<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+	let who = account(name, index, SEED);
+	T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value() / 2u32.into());
+	who
+}
+
+// 生成第 index 个长度为 len 的存证
+fn claim_data(index: u32, len: u32) -> Vec<u8> {
+	let mut claim = index.encode();
+	claim.resize(len.max(claim.len() as u32) as usize, 0);
+	claim
+}
+
+fn create_claim_of<T: Trait>(owner: &T::AccountId, index: u32) -> Result<Vec<u8>, &'static str> {
+	let claim = claim_data(index, T::MaxClaimLength::get());
+	Module::<T>::do_create_claim(owner.clone(), claim.clone())?;
+	Ok(claim)
+}
+
+// 填满所有权历史, 让转移走丢弃最早记录的分支
+fn fill_history<T: Trait>(claim: &[u8], owner: &T::AccountId) {
+	let history = (0..T::MaxHistoryLength::get())
+		.map(|_| (owner.clone(), Zero::zero()))
+		.collect::<Vec<_>>();
+	ClaimHistory::<T>::insert(claim, history);
+}
+
+benchmarks! {
+	_ { }
+
+	create_claim {
+		let l in 1 .. T::MaxClaimLength::get();
+		let caller = funded_account::<T>("caller", 0);
+		let claim = vec![1u8; l as usize];
+	}: _(RawOrigin::Signed(caller.clone()), claim.clone())
+	verify {
+		assert_eq!(Module::<T>::proof_of(&claim).map(|(owner, _)| owner), Some(caller));
+	}
+
+	create_claim_hashed {
+		let caller = funded_account::<T>("caller", 0);
+		let digest = Module::<T>::hash_claim(b"document");
+	}: _(RawOrigin::Signed(caller.clone()), digest)
+	verify {
+		assert_eq!(Module::<T>::proof_of(digest.as_ref()).map(|(owner, _)| owner), Some(caller));
+	}
+
+	create_claims_batch {
+		let n in 1 .. T::MaxBatchSize::get();
+		let caller = funded_account::<T>("caller", 0);
+		let claims = (0..n).map(|i| claim_data(i, T::MaxClaimLength::get())).collect::<Vec<_>>();
+	}: _(RawOrigin::Signed(caller.clone()), claims.clone())
+	verify {
+		for claim in claims.iter() {
+			assert!(Proofs::<T>::contains_key(claim));
+		}
+	}
+
+	anchor_merkle_root {
+		let caller = funded_account::<T>("caller", 0);
+		let root = Module::<T>::hash_claim(b"root");
+	}: _(RawOrigin::Signed(caller.clone()), root, 1024)
+	verify {
+		assert_eq!(Module::<T>::anchored_root(root.as_ref().to_vec()), Some(1024));
+	}
+
+	revoke_claim {
+		let caller = funded_account::<T>("caller", 0);
+		let claim = create_claim_of::<T>(&caller, 0)?;
+		Module::<T>::offer_claim(RawOrigin::Signed(caller.clone()).into(), claim.clone(), account("dest", 0, SEED))?;
+	}: _(RawOrigin::Signed(caller.clone()), claim.clone())
+	verify {
+		assert!(!Proofs::<T>::contains_key(&claim));
+	}
+
+	transfer_claim {
+		let caller = funded_account::<T>("caller", 0);
+		let dest = funded_account::<T>("dest", 0);
+		let claim = create_claim_of::<T>(&caller, 0)?;
+		fill_history::<T>(&claim, &caller);
+	}: _(RawOrigin::Signed(caller.clone()), claim.clone(), dest.clone())
+	verify {
+		assert_eq!(Module::<T>::proof_of(&claim).map(|(owner, _)| owner), Some(dest));
+	}
+
+	offer_claim {
+		let caller = funded_account::<T>("caller", 0);
+		let dest: T::AccountId = account("dest", 0, SEED);
+		let claim = create_claim_of::<T>(&caller, 0)?;
+	}: _(RawOrigin::Signed(caller.clone()), claim.clone(), dest.clone())
+	verify {
+		assert_eq!(Module::<T>::claim_offer(&claim), Some(dest));
+	}
+
+	accept_claim {
+		let caller = funded_account::<T>("caller", 0);
+		let dest = funded_account::<T>("dest", 0);
+		let claim = create_claim_of::<T>(&caller, 0)?;
+		fill_history::<T>(&claim, &caller);
+		Module::<T>::offer_claim(RawOrigin::Signed(caller.clone()).into(), claim.clone(), dest.clone())?;
+	}: _(RawOrigin::Signed(dest.clone()), claim.clone())
+	verify {
+		assert_eq!(Module::<T>::proof_of(&claim).map(|(owner, _)| owner), Some(dest));
+	}
+
+	cancel_claim_offer {
+		let caller = funded_account::<T>("caller", 0);
+		let claim = create_claim_of::<T>(&caller, 0)?;
+		Module::<T>::offer_claim(RawOrigin::Signed(caller.clone()).into(), claim.clone(), account("dest", 0, SEED))?;
+	}: _(RawOrigin::Signed(caller.clone()), claim.clone())
+	verify {
+		assert_eq!(Module::<T>::claim_offer(&claim), None);
+	}
+
+	set_claim_metadata {
+		let m in 0 .. T::MaxMemoLength::get();
+		let caller = funded_account::<T>("caller", 0);
+		let claim = create_claim_of::<T>(&caller, 0)?;
+		let expiry = frame_system::Module::<T>::block_number() + 10u32.into();
+		// 替换已有的过期时间, 走更新过期索引的两个分支
+		Module::<T>::set_claim_metadata(
+			RawOrigin::Signed(caller.clone()).into(), claim.clone(), vec![], Some(expiry + 1u32.into()),
+		)?;
+	}: _(RawOrigin::Signed(caller.clone()), claim.clone(), vec![0u8; m as usize], Some(expiry))
+	verify {
+		assert_eq!(Module::<T>::proofs(&claim).and_then(|info| info.expires_at), Some(expiry));
+	}
+
+	on_initialize {
+		let e in 0 .. T::MaxExpiriesPerBlock::get();
+		let caller = funded_account::<T>("caller", 0);
+		let expiry = frame_system::Module::<T>::block_number() + 10u32.into();
+		for i in 0 .. e {
+			let claim = create_claim_of::<T>(&caller, i)?;
+			Module::<T>::set_claim_metadata(RawOrigin::Signed(caller.clone()).into(), claim, vec![], Some(expiry))?;
+		}
+	}: {
+		Module::<T>::on_initialize(expiry);
+	}
+	verify {
+		assert!(Module::<T>::claim_expiries(expiry).is_empty());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_create_claim::<Test>());
+			assert_ok!(test_benchmark_create_claim_hashed::<Test>());
+			assert_ok!(test_benchmark_create_claims_batch::<Test>());
+			assert_ok!(test_benchmark_anchor_merkle_root::<Test>());
+			assert_ok!(test_benchmark_revoke_claim::<Test>());
+			assert_ok!(test_benchmark_transfer_claim::<Test>());
+			assert_ok!(test_benchmark_offer_claim::<Test>());
+			assert_ok!(test_benchmark_accept_claim::<Test>());
+			assert_ok!(test_benchmark_cancel_claim_offer::<Test>());
+			assert_ok!(test_benchmark_set_claim_metadata::<Test>());
+			assert_ok!(test_benchmark_on_initialize::<Test>());
+		});
+	}
+}
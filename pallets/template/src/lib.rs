@@ -5,9 +5,14 @@
 /// https://substrate.dev/docs/en/knowledgebase/runtime/frame
 
 use frame_support::{
-	decl_module, decl_storage, decl_event, decl_error, dispatch, ensure,
+	decl_module, decl_storage, decl_event, decl_error, dispatch, ensure, transactional,
+	storage::IterableStorageMap,
+	traits::{Get, Currency, ReservableCurrency, BalanceStatus},
+	weights::Weight,
 };
 use frame_system::ensure_signed;
+use codec::{Encode, Decode};
+use sp_runtime::{RuntimeDebug, traits::{Hash, Saturating, Zero}};
 use sp_std::vec::Vec;
 
 #[cfg(test)]
@@ -16,10 +21,65 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+mod benchmarking;
+pub mod weights;
+
+pub use weights::WeightInfo;
+
 /// Configure the pallet by specifying the parameters and types on which it depends.
 pub trait Trait: frame_system::Trait {
 	/// Because this pallet emits events, it depends on the runtime's definition of an event.
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+	/// The maximum length of a raw claim passed to `create_claim`.
+	type MaxClaimLength: Get<u32>;
+	/// The hashing algorithm clients use to digest documents for `create_claim_hashed`.
+	type ClaimHashing: Hash<Output = Self::Hash>;
+	/// The currency in which claim deposits are reserved.
+	type Currency: ReservableCurrency<Self::AccountId>;
+	/// The base deposit reserved for every claim.
+	type ClaimDepositBase: Get<BalanceOf<Self>>;
+	/// The additional deposit reserved per byte of claim and memo.
+	type ClaimDepositPerByte: Get<BalanceOf<Self>>;
+	/// The maximum length of the memo attached to a claim.
+	type MaxMemoLength: Get<u32>;
+	/// The maximum number of ownership records kept for a claim.
+	type MaxHistoryLength: Get<u32>;
+	/// The maximum number of claims that may expire in the same block.
+	type MaxExpiriesPerBlock: Get<u32>;
+	/// The maximum number of claims in one `create_claims_batch` call.
+	type MaxBatchSize: Get<u32>;
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+
+/// 存证的详细信息
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ClaimInfo<AccountId, BlockNumber> {
+	/// 当前拥有者
+	pub owner: AccountId,
+	/// 存证创建的区块
+	pub created_at: BlockNumber,
+	/// 备注或 URI
+	pub memo: Vec<u8>,
+	/// 存证过期的区块, None 表示永不过期
+	pub expires_at: Option<BlockNumber>,
+}
+
+/// 存储格式的版本
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	/// `Proofs` 存储 `(T::AccountId, T::BlockNumber)`
+	V1_0_0,
+	/// `Proofs` 存储 `ClaimInfo`
+	V2_0_0,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1_0_0
+	}
 }
 
 // The pallet's runtime storage items.
@@ -29,16 +89,81 @@ decl_storage! {
 	// This name may be updated, but each pallet in the runtime must use a unique name.
 	// ---------------------------------vvvvvvvvvvvvvv
 	trait Store for Module<T: Trait> as TemplateModule {
-		Proofs: map hasher(blake2_128_concat) Vec<u8> => (T::AccountId, T::BlockNumber);
+		/// 所有的存证
+		pub Proofs get(fn proofs):
+			map hasher(blake2_128_concat) Vec<u8> => Option<ClaimInfo<T::AccountId, T::BlockNumber>>;
+
+		/// 每个存证的拥有者质押的押金
+		pub ClaimDeposits get(fn claim_deposit): map hasher(blake2_128_concat) Vec<u8> => BalanceOf<T>;
+
+		/// 存证的所有权历史, 每一项是 (拥有者, 获得所有权的区块), 最多保留 `MaxHistoryLength` 项
+		pub ClaimHistory get(fn claim_history):
+			map hasher(blake2_128_concat) Vec<u8> => Vec<(T::AccountId, T::BlockNumber)>;
+
+		/// 等待接受的存证转移提议, 值是被提议的新拥有者
+		pub ClaimOffers get(fn claim_offer): map hasher(blake2_128_concat) Vec<u8> => Option<T::AccountId>;
+
+		/// 锚定的默克尔根 (以存证的形式保存在 `Proofs` 中) 及其叶子数量
+		pub AnchoredRoots get(fn anchored_root): map hasher(blake2_128_concat) Vec<u8> => Option<u32>;
+
+		/// 每个区块过期的存证
+		pub ClaimExpiries get(fn claim_expiries): map hasher(twox_64_concat) T::BlockNumber => Vec<Vec<u8>>;
+
+		/// 存储格式的版本, 用于存储迁移
+		StorageVersion build(|_| Releases::V2_0_0): Releases;
+	}
+	add_extra_genesis {
+		/// 创世时登记的存证: (拥有者, 存证内容), 押金从拥有者的余额中质押
+		config(proofs): Vec<(T::AccountId, Vec<u8>)>;
+		build(|config: &GenesisConfig<T>| {
+			for (owner, claim) in &config.proofs {
+				assert!(claim.len() <= T::MaxClaimLength::get() as usize, "genesis proof is too long");
+				assert!(!Proofs::<T>::contains_key(claim), "duplicate genesis proof");
+
+				let deposit = Module::<T>::deposit_for(claim.len());
+				T::Currency::reserve(owner, deposit).expect("genesis proof owner cannot afford the deposit");
+
+				Proofs::<T>::insert(claim, ClaimInfo {
+					owner: owner.clone(),
+					created_at: frame_system::Module::<T>::block_number(),
+					memo: Vec::new(),
+					expires_at: None,
+				});
+				ClaimDeposits::<T>::insert(claim, deposit);
+				Module::<T>::record_owner(claim, owner);
+			}
+		});
 	}
 }
 
 // Pallets use events to inform users when important changes are made.
 // https://substrate.dev/docs/en/knowledgebase/runtime/events
 decl_event!(
-	pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId {
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Trait>::AccountId,
+		Hash = <T as frame_system::Trait>::Hash,
+		Balance = BalanceOf<T>,
+	{
 		ClaimCreated(AccountId, Vec<u8>),
 		ClaimRevoked(AccountId, Vec<u8>),
+		/// 为存证质押了押金
+		ClaimDepositReserved(AccountId, Vec<u8>, Balance),
+		/// 存证的押金被释放
+		ClaimDepositUnreserved(AccountId, Vec<u8>, Balance),
+		/// 存证的押金随存证转移给了新的拥有者
+		ClaimDepositMoved(AccountId, AccountId, Vec<u8>, Balance),
+		/// 存证的备注和过期时间被更新
+		ClaimMetadataSet(AccountId, Vec<u8>),
+		/// 存证已过期并被移除
+		ClaimExpired(AccountId, Vec<u8>),
+		/// 存证的所有权被转移 (from, to, claim)
+		ClaimTransferred(AccountId, AccountId, Vec<u8>),
+		/// 存证被提议转移给另一个账户 (from, to, claim)
+		ClaimOffered(AccountId, AccountId, Vec<u8>),
+		/// 存证的转移提议被取消
+		ClaimOfferCancelled(AccountId, Vec<u8>),
+		/// 锚定了一个默克尔根 (owner, root, leaf_count)
+		MerkleRootAnchored(AccountId, Hash, u32),
 	}
 );
 
@@ -53,6 +178,26 @@ decl_error! {
 		NotClaimOwner,
 		/// 存证数据太长
 		ClaimTooLong,
+		/// 余额不足以支付存证押金
+		InsufficientBalance,
+		/// 备注太长
+		MemoTooLong,
+		/// 过期区块必须在当前区块之后
+		InvalidExpiry,
+		/// 同一区块过期的存证太多
+		TooManyExpiries,
+		/// 不能把存证转移给自己
+		TransferToSelf,
+		/// 存证的转移提议不存在
+		ClaimOfferNotExist,
+		/// 不是转移提议的接收者
+		NotOfferRecipient,
+		/// 批量存证的数量太多
+		BatchTooLarge,
+		/// 默克尔树的叶子数量不能为 0
+		EmptyMerkleTree,
+		/// 接收者账户不存在, 无法接收存证押金
+		RecipientNotExist,
 	}
 }
 
@@ -67,59 +212,423 @@ decl_module! {
 		// Events must be initialized if they are used by the pallet.
 		fn deposit_event() = default;
 
+		/// 存证数据的最大长度
+		const MaxClaimLength: u32 = T::MaxClaimLength::get();
+
+		/// 每个存证的基础押金
+		const ClaimDepositBase: BalanceOf<T> = T::ClaimDepositBase::get();
+
+		/// 存证每个字节的押金
+		const ClaimDepositPerByte: BalanceOf<T> = T::ClaimDepositPerByte::get();
+
+		/// 备注的最大长度
+		const MaxMemoLength: u32 = T::MaxMemoLength::get();
+
+		/// 每个存证保留的所有权历史的最大长度
+		const MaxHistoryLength: u32 = T::MaxHistoryLength::get();
+
+		/// 同一区块最多过期的存证数量
+		const MaxExpiriesPerBlock: u32 = T::MaxExpiriesPerBlock::get();
+
+		/// 一次批量存证的最大数量
+		const MaxBatchSize: u32 = T::MaxBatchSize::get();
+
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get() == Releases::V1_0_0 {
+				Self::migrate_to_v2()
+			} else {
+				0
+			}
+		}
+
+		/// 移除在这个区块过期的存证
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let expiring = ClaimExpiries::<T>::take(now);
+
+			for claim in expiring.iter() {
+				if let Some(info) = Self::proofs(claim) {
+					if info.expires_at == Some(now) {
+						Self::remove_claim(claim, &info);
+						Self::deposit_event(RawEvent::ClaimExpired(info.owner, claim.clone()));
+					}
+				}
+			}
+
+			T::WeightInfo::on_initialize(expiring.len() as u32)
+		}
+
 		/// 创建存证
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::create_claim(claim.len() as u32)]
 		pub fn create_claim(origin, claim: Vec<u8>) -> dispatch::DispatchResult {
 			let sender = ensure_signed(origin)?;
 
 			// 这里限制一下存证数据的长度
-			ensure!(claim.len() <= 10, Error::<T>::ClaimTooLong);
+			ensure!(claim.len() <= T::MaxClaimLength::get() as usize, Error::<T>::ClaimTooLong);
 
-			ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+			Self::do_create_claim(sender, claim)
+		}
 
-			let current_block = frame_system::Module::<T>::block_number();
+		/// 用文件的哈希创建存证, 哈希算法由 `T::ClaimHashing` 决定
+		#[weight = T::WeightInfo::create_claim_hashed()]
+		pub fn create_claim_hashed(origin, digest: T::Hash) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			Self::do_create_claim(sender, digest.as_ref().to_vec())
+		}
+
+		/// 批量创建存证, 任何一个存证失败时整个批次都不会生效
+		#[weight = T::WeightInfo::create_claims_batch(claims.len() as u32)]
+		#[transactional]
+		pub fn create_claims_batch(origin, claims: Vec<Vec<u8>>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(claims.len() <= T::MaxBatchSize::get() as usize, Error::<T>::BatchTooLarge);
+
+			for claim in claims {
+				ensure!(claim.len() <= T::MaxClaimLength::get() as usize, Error::<T>::ClaimTooLong);
+				Self::do_create_claim(sender.clone(), claim)?;
+			}
+
+			Ok(())
+		}
+
+		/// 锚定一棵默克尔树的根, 只保存根和叶子数量
+		///
+		/// 根作为存证保存在 `Proofs` 中, 可以像普通存证一样撤销、转移和设置过期时间.
+		/// 树的构造方式见 `merkle_root`.
+		#[weight = T::WeightInfo::anchor_merkle_root()]
+		pub fn anchor_merkle_root(origin, root: T::Hash, leaf_count: u32) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
 
-			Proofs::<T>::insert(&claim, (sender.clone(), current_block));
+			ensure!(leaf_count > 0, Error::<T>::EmptyMerkleTree);
 
-			Self::deposit_event(RawEvent::ClaimCreated(sender, claim));
+			let claim = root.as_ref().to_vec();
+			Self::do_create_claim(sender.clone(), claim.clone())?;
+			AnchoredRoots::insert(&claim, leaf_count);
+
+			Self::deposit_event(RawEvent::MerkleRootAnchored(sender, root, leaf_count));
 
 			Ok(())
 		}
 
 		/// 移除存证
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::revoke_claim()]
 		pub fn revoke_claim(origin, claim: Vec<u8>) -> dispatch::DispatchResult {
 			let sender = ensure_signed(origin)?;
 
-			ensure!(Proofs::<T>::contains_key(&claim), Error::<T>::ClaimNotExist);
-
-			let (owner, _block_number) = Proofs::<T>::get(&claim);
+			let info = Self::proofs(&claim).ok_or(Error::<T>::ClaimNotExist)?;
 
-			ensure!(sender == owner, Error::<T>::NotClaimOwner);
+			ensure!(sender == info.owner, Error::<T>::NotClaimOwner);
 
-			Proofs::<T>::remove(&claim);
+			// 移除存证并释放押金
+			let deposit = Self::remove_claim(&claim, &info);
 
+			Self::deposit_event(RawEvent::ClaimDepositUnreserved(sender.clone(), claim.clone(), deposit));
 			Self::deposit_event(RawEvent::ClaimRevoked(sender, claim));
 
 			Ok(())
 		}
 
 		/// 转移存证的所有权
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::transfer_claim()]
 		pub fn transfer_claim(origin, claim: Vec<u8>, dest: T::AccountId) -> dispatch::DispatchResult {
 			let sender = ensure_signed(origin)?;
 
-			ensure!(Proofs::<T>::contains_key(&claim), Error::<T>::ClaimNotExist);
+			let info = Self::proofs(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			ensure!(info.owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(dest != sender, Error::<T>::TransferToSelf);
+
+			Self::do_transfer_claim(claim, info, dest)
+		}
+
+		/// 把存证提供给另一个账户, 对方调用 `accept_claim` 后才会转移
+		#[weight = T::WeightInfo::offer_claim()]
+		pub fn offer_claim(origin, claim: Vec<u8>, dest: T::AccountId) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
 
-			let (owner, _block_number) = Proofs::<T>::get(&claim);
+			let info = Self::proofs(&claim).ok_or(Error::<T>::ClaimNotExist)?;
 
-			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(info.owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(dest != sender, Error::<T>::TransferToSelf);
 
-			let block_number = frame_system::Module::<T>::block_number();
+			// 新的提议会覆盖之前的提议
+			ClaimOffers::<T>::insert(&claim, &dest);
 
-			Proofs::<T>::insert(&claim, (dest, block_number));
+			Self::deposit_event(RawEvent::ClaimOffered(sender, dest, claim));
 
 			Ok(())
 		}
+
+		/// 接受别人提供的存证
+		#[weight = T::WeightInfo::accept_claim()]
+		pub fn accept_claim(origin, claim: Vec<u8>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let info = Self::proofs(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+			let dest = Self::claim_offer(&claim).ok_or(Error::<T>::ClaimOfferNotExist)?;
+
+			ensure!(dest == sender, Error::<T>::NotOfferRecipient);
+
+			Self::do_transfer_claim(claim, info, dest)
+		}
+
+		/// 取消存证的转移提议
+		#[weight = T::WeightInfo::cancel_claim_offer()]
+		pub fn cancel_claim_offer(origin, claim: Vec<u8>) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			let info = Self::proofs(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			ensure!(info.owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(ClaimOffers::<T>::contains_key(&claim), Error::<T>::ClaimOfferNotExist);
+
+			ClaimOffers::<T>::remove(&claim);
+
+			Self::deposit_event(RawEvent::ClaimOfferCancelled(sender, claim));
+
+			Ok(())
+		}
+
+		/// 设置存证的备注和过期区块, 押金按存证和备注的总长度重新计算
+		#[weight = T::WeightInfo::set_claim_metadata(memo.len() as u32)]
+		pub fn set_claim_metadata(
+			origin,
+			claim: Vec<u8>,
+			memo: Vec<u8>,
+			expires_at: Option<T::BlockNumber>,
+		) -> dispatch::DispatchResult {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(memo.len() <= T::MaxMemoLength::get() as usize, Error::<T>::MemoTooLong);
+
+			let mut info = Self::proofs(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			ensure!(info.owner == sender, Error::<T>::NotClaimOwner);
+
+			if let Some(expiry) = expires_at {
+				ensure!(expiry > frame_system::Module::<T>::block_number(), Error::<T>::InvalidExpiry);
+				if info.expires_at != expires_at {
+					ensure!(
+						(Self::claim_expiries(expiry).len() as u32) < T::MaxExpiriesPerBlock::get(),
+						Error::<T>::TooManyExpiries
+					);
+				}
+			}
+
+			// 调整押金
+			let old_deposit = Self::claim_deposit(&claim);
+			let new_deposit = Self::deposit_for(claim.len() + memo.len());
+			if new_deposit > old_deposit {
+				T::Currency::reserve(&sender, new_deposit - old_deposit)
+					.map_err(|_| Error::<T>::InsufficientBalance)?;
+			} else {
+				T::Currency::unreserve(&sender, old_deposit - new_deposit);
+			}
+			ClaimDeposits::<T>::insert(&claim, new_deposit);
+
+			// 更新过期索引
+			if info.expires_at != expires_at {
+				if let Some(old_expiry) = info.expires_at {
+					Self::unschedule_expiry(old_expiry, &claim);
+				}
+				if let Some(new_expiry) = expires_at {
+					ClaimExpiries::<T>::mutate(new_expiry, |claims| claims.push(claim.clone()));
+				}
+			}
+
+			info.memo = memo;
+			info.expires_at = expires_at;
+			Proofs::<T>::insert(&claim, info);
+
+			Self::deposit_event(RawEvent::ClaimMetadataSet(sender, claim));
+
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	fn do_create_claim(sender: T::AccountId, claim: Vec<u8>) -> dispatch::DispatchResult {
+		ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+
+		// 质押押金, 押金与存证的长度相关
+		let deposit = Self::deposit_for(claim.len());
+		T::Currency::reserve(&sender, deposit).map_err(|_| Error::<T>::InsufficientBalance)?;
+
+		let current_block = frame_system::Module::<T>::block_number();
+
+		Proofs::<T>::insert(&claim, ClaimInfo {
+			owner: sender.clone(),
+			created_at: current_block,
+			memo: Vec::new(),
+			expires_at: None,
+		});
+		ClaimDeposits::<T>::insert(&claim, deposit);
+		Self::record_owner(&claim, &sender);
+
+		Self::deposit_event(RawEvent::ClaimDepositReserved(sender.clone(), claim.clone(), deposit));
+		Self::deposit_event(RawEvent::ClaimCreated(sender, claim));
+
+		Ok(())
+	}
+
+	fn do_transfer_claim(
+		claim: Vec<u8>,
+		mut info: ClaimInfo<T::AccountId, T::BlockNumber>,
+		dest: T::AccountId,
+	) -> dispatch::DispatchResult {
+		let from = info.owner.clone();
+
+		// 押金随存证一起转移给新的拥有者, 接收者账户必须已经存在
+		ensure!(!T::Currency::total_balance(&dest).is_zero(), Error::<T>::RecipientNotExist);
+		let deposit = Self::claim_deposit(&claim);
+		T::Currency::repatriate_reserved(&from, &dest, deposit, BalanceStatus::Reserved)?;
+
+		// 创建区块保持不变, 所有权的变化记录在历史中
+		info.owner = dest.clone();
+		Proofs::<T>::insert(&claim, info);
+		ClaimOffers::<T>::remove(&claim);
+		Self::record_owner(&claim, &dest);
+
+		Self::deposit_event(RawEvent::ClaimDepositMoved(from.clone(), dest.clone(), claim.clone(), deposit));
+		Self::deposit_event(RawEvent::ClaimTransferred(from, dest, claim));
+
+		Ok(())
+	}
+
+	/// 移除存证及其押金、历史、转移提议和过期索引, 返回释放的押金
+	fn remove_claim(claim: &[u8], info: &ClaimInfo<T::AccountId, T::BlockNumber>) -> BalanceOf<T> {
+		Proofs::<T>::remove(claim);
+		ClaimHistory::<T>::remove(claim);
+		ClaimOffers::<T>::remove(claim);
+		AnchoredRoots::remove(claim);
+		if let Some(expiry) = info.expires_at {
+			Self::unschedule_expiry(expiry, claim);
+		}
+
+		let deposit = ClaimDeposits::<T>::take(claim);
+		T::Currency::unreserve(&info.owner, deposit);
+		deposit
+	}
+
+	/// 从过期索引中移除存证, 索引变空时删除整个条目
+	fn unschedule_expiry(expiry: T::BlockNumber, claim: &[u8]) {
+		ClaimExpiries::<T>::mutate_exists(expiry, |claims| {
+			if let Some(list) = claims.as_mut() {
+				list.retain(|c| c.as_slice() != claim);
+			}
+			if claims.as_ref().map_or(false, |list| list.is_empty()) {
+				*claims = None;
+			}
+		});
+	}
+
+	/// 在所有权历史中记录新的拥有者, 超出 `MaxHistoryLength` 时丢弃最早的记录
+	fn record_owner(claim: &[u8], owner: &T::AccountId) {
+		let now = frame_system::Module::<T>::block_number();
+		let max = T::MaxHistoryLength::get() as usize;
+
+		ClaimHistory::<T>::mutate(claim, |history| {
+			history.push((owner.clone(), now));
+			if history.len() > max {
+				let excess = history.len() - max;
+				history.drain(..excess);
+			}
+		});
+	}
+
+	/// 把 `Proofs` 从 `(T::AccountId, T::BlockNumber)` 迁移到 `ClaimInfo`
+	fn migrate_to_v2() -> Weight {
+		let migrated = sp_std::cell::Cell::new(0 as Weight);
+
+		Proofs::<T>::translate::<(T::AccountId, T::BlockNumber), _>(|claim, (owner, created_at)| {
+			migrated.set(migrated.get() + 1);
+			ClaimHistory::<T>::insert(&claim, sp_std::vec![(owner.clone(), created_at)]);
+			Some(ClaimInfo { owner, created_at, memo: Vec::new(), expires_at: None })
+		});
+
+		StorageVersion::put(Releases::V2_0_0);
+
+		let migrated = migrated.get();
+		T::DbWeight::get().reads_writes(migrated + 1, migrated * 2 + 1)
+	}
+
+	/// 计算一个存证需要质押的押金, `len` 是存证和备注的总长度
+	pub fn deposit_for(len: usize) -> BalanceOf<T> {
+		T::ClaimDepositPerByte::get()
+			.saturating_mul((len as u32).into())
+			.saturating_add(T::ClaimDepositBase::get())
+	}
+
+	/// 用 `T::ClaimHashing` 计算文件的哈希, 结果可以用于 `create_claim_hashed`
+	pub fn hash_claim(document: &[u8]) -> T::Hash {
+		T::ClaimHashing::hash(document)
+	}
+
+	/// 计算一对节点的父节点
+	fn merkle_parent(left: &T::Hash, right: &T::Hash) -> T::Hash {
+		let mut data = Vec::with_capacity(left.as_ref().len() + right.as_ref().len());
+		data.extend_from_slice(left.as_ref());
+		data.extend_from_slice(right.as_ref());
+		T::ClaimHashing::hash(&data)
+	}
+
+	/// 叶子数量为 `leaf_count` 的默克尔树的深度, 也就是证明的长度
+	fn merkle_depth(leaf_count: u32) -> usize {
+		let mut depth = 0;
+		let mut width = leaf_count;
+		while width > 1 {
+			width = width / 2 + width % 2;
+			depth += 1;
+		}
+		depth
+	}
+
+	/// 计算默克尔根
+	///
+	/// 父节点是 `T::ClaimHashing(left ++ right)`, 某一层的节点数为奇数时最后一个节点和自己配对.
+	/// 没有叶子时返回默认哈希.
+	pub fn merkle_root(leaves: &[T::Hash]) -> T::Hash {
+		let mut level = leaves.to_vec();
+		while level.len() > 1 {
+			level = level
+				.chunks(2)
+				.map(|pair| Self::merkle_parent(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+				.collect();
+		}
+		level.pop().unwrap_or_default()
+	}
+
+	/// 验证 `leaf` 是锚定的默克尔根 `root` 的第 `index` 个叶子
+	///
+	/// `proof` 是从叶子到根每一层的兄弟节点, 长度必须等于树的深度.
+	pub fn verify_inclusion(root: T::Hash, leaf: T::Hash, index: u32, proof: Vec<T::Hash>) -> bool {
+		let leaf_count = match Self::anchored_root(root.as_ref().to_vec()) {
+			Some(leaf_count) => leaf_count,
+			None => return false,
+		};
+
+		if index >= leaf_count || proof.len() != Self::merkle_depth(leaf_count) {
+			return false;
+		}
+
+		let mut node = leaf;
+		let mut position = index;
+		for sibling in proof.iter() {
+			node = if position % 2 == 0 {
+				Self::merkle_parent(&node, sibling)
+			} else {
+				Self::merkle_parent(sibling, &node)
+			};
+			position /= 2;
+		}
+
+		node == root
+	}
+
+	/// 查询存证的拥有者和创建区块, 存证不存在时返回 None
+	pub fn proof_of(claim: &[u8]) -> Option<(T::AccountId, T::BlockNumber)> {
+		Self::proofs(claim).map(|info| (info.owner, info.created_at))
 	}
 }
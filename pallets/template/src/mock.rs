@@ -1,10 +1,11 @@
 use crate::{Module, Trait};
 use sp_core::H256;
-use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use frame_support::{impl_outer_origin, impl_outer_event, parameter_types, weights::Weight};
 use sp_runtime::{
 	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
 };
 use frame_system as system;
+use balances;
 
 impl_outer_origin! {
 	pub enum Origin for Test {}
@@ -19,6 +20,14 @@ parameter_types! {
 	pub const MaximumBlockWeight: Weight = 1024;
 	pub const MaximumBlockLength: u32 = 2 * 1024;
 	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	pub const MaxClaimLength: u32 = 10;
+	pub const ExistentialDeposit: u64 = 1;
+	pub const ClaimDepositBase: u64 = 2;
+	pub const ClaimDepositPerByte: u64 = 1;
+	pub const MaxMemoLength: u32 = 16;
+	pub const MaxHistoryLength: u32 = 3;
+	pub const MaxExpiriesPerBlock: u32 = 2;
+	pub const MaxBatchSize: u32 = 3;
 }
 
 impl system::Trait for Test {
@@ -32,7 +41,7 @@ impl system::Trait for Test {
 	type AccountId = u64;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
-	type Event = ();
+	type Event = TestEvent;
 	type BlockHashCount = BlockHashCount;
 	type MaximumBlockWeight = MaximumBlockWeight;
 	type DbWeight = ();
@@ -43,19 +52,71 @@ impl system::Trait for Test {
 	type AvailableBlockRatio = AvailableBlockRatio;
 	type Version = ();
 	type PalletInfo = ();
-	type AccountData = ();
+	type AccountData = balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
 }
 
+impl balances::Trait for Test {
+	type Balance = u64;
+	type MaxLocks = ();
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = system::Module<Test>;
+	type WeightInfo = ();
+}
+
+mod template {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		system<T>,
+		balances<T>,
+		template<T>,
+	}
+}
+
 impl Trait for Test {
-	type Event = ();
+	type Event = TestEvent;
+	type MaxClaimLength = MaxClaimLength;
+	type ClaimHashing = BlakeTwo256;
+	type Currency = balances::Module<Test>;
+	type ClaimDepositBase = ClaimDepositBase;
+	type ClaimDepositPerByte = ClaimDepositPerByte;
+	type MaxMemoLength = MaxMemoLength;
+	type MaxHistoryLength = MaxHistoryLength;
+	type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+	type MaxBatchSize = MaxBatchSize;
+	type WeightInfo = ();
 }
 
 pub type TemplateModule = Module<Test>;
+pub type Balances = balances::Module<Test>;
+pub type System = system::Module<Test>;
+
+pub fn last_event() -> TestEvent {
+	system::Module::<Test>::events()
+		.pop()
+		.expect("Event expected")
+		.event
+}
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	new_test_ext_with_proofs(vec![])
+}
+
+// 带有创世存证的测试环境
+pub fn new_test_ext_with_proofs(proofs: Vec<(u64, Vec<u8>)>) -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	balances::GenesisConfig::<Test> {
+		// 账户 3 只够支付最短存证的押金
+		balances: vec![(1, 100), (2, 100), (3, 4), (10, 100)],
+	}.assimilate_storage(&mut t).unwrap();
+	crate::GenesisConfig::<Test> { proofs }.assimilate_storage(&mut t).unwrap();
+	t.into()
 }
@@ -1,5 +1,9 @@
 use crate::{Error, mock::*};
-use frame_support::{assert_ok, assert_noop};
+use sp_core::H256;
+use frame_support::{
+    assert_ok, assert_noop,
+    traits::{Currency, ReservableCurrency, OnInitialize, OnRuntimeUpgrade},
+};
 use super::*;
 
 #[test]
@@ -10,8 +14,14 @@ fn create_claim_works() {
 
         assert_eq!(
             Proofs::<Test>::get(&claim),
-            (1, frame_system::Module::<Test>::block_number())
+            Some(ClaimInfo {
+                owner: 1,
+                created_at: frame_system::Module::<Test>::block_number(),
+                memo: vec![],
+                expires_at: None,
+            })
         );
+        assert_eq!(TemplateModule::claim_history(&claim), vec![(1, 0)]);
     })
 }
 
@@ -77,6 +87,21 @@ fn transfer_claim_failed_when_claim_no_exist() {
     })
 }
 
+#[test]
+fn transfer_claim_failed_when_recipient_has_no_balance() {
+    new_test_ext().execute_with(|| {
+        let claim = vec![0, 1];
+        let _ = TemplateModule::create_claim(Origin::signed(1), claim.clone());
+
+        let dest: u64 = 42;
+        assert_eq!(Balances::total_balance(&dest), 0);
+        assert_noop!(
+            TemplateModule::transfer_claim(Origin::signed(1), claim.clone(), dest),
+            Error::<Test>::RecipientNotExist
+        );
+    })
+}
+
 #[test]
 fn transfer_claim_too_long() {
     new_test_ext().execute_with(|| {
@@ -87,4 +112,471 @@ fn transfer_claim_too_long() {
             Error::<Test>::ClaimTooLong
         );
     })
-}
\ No newline at end of file
+}
+
+#[test]
+fn proof_of_works() {
+    new_test_ext().execute_with(|| {
+        let claim = vec![0, 1];
+        assert_eq!(TemplateModule::proof_of(&claim), None);
+
+        let _ = TemplateModule::create_claim(Origin::signed(1), claim.clone());
+
+        assert_eq!(
+            TemplateModule::proof_of(&claim),
+            Some((1, frame_system::Module::<Test>::block_number()))
+        );
+    })
+}
+
+#[test]
+fn create_claim_max_length_works() {
+    new_test_ext().execute_with(|| {
+        let claim = vec![7; MaxClaimLength::get() as usize];
+
+        assert_ok!(TemplateModule::create_claim(Origin::signed(1), claim.clone()));
+        assert_eq!(TemplateModule::proof_of(&claim).map(|(owner, _)| owner), Some(1));
+    })
+}
+
+#[test]
+fn create_claim_hashed_works() {
+    new_test_ext().execute_with(|| {
+        // 远超 MaxClaimLength 的文件也可以通过哈希存证
+        let document = vec![42u8; 4096];
+        let digest = TemplateModule::hash_claim(&document);
+        assert_eq!(digest, <sp_runtime::traits::BlakeTwo256 as Hash>::hash(&document));
+
+        assert_ok!(TemplateModule::create_claim_hashed(Origin::signed(1), digest));
+        assert_eq!(
+            TemplateModule::proof_of(digest.as_ref()),
+            Some((1, frame_system::Module::<Test>::block_number()))
+        );
+
+        assert_noop!(
+            TemplateModule::create_claim_hashed(Origin::signed(2), digest),
+            Error::<Test>::ProofAlreadyExist
+        );
+
+        assert_ok!(TemplateModule::revoke_claim(Origin::signed(1), digest.as_ref().to_vec()));
+        assert_eq!(TemplateModule::proof_of(digest.as_ref()), None);
+    })
+}
+
+#[test]
+fn create_claim_reserves_deposit() {
+    new_test_ext().execute_with(|| {
+        let claim = vec![0, 1, 2];
+        assert_ok!(TemplateModule::create_claim(Origin::signed(1), claim.clone()));
+
+        // 基础押金 2 + 每字节 1
+        let deposit = 2 + 3;
+        assert_eq!(TemplateModule::deposit_for(claim.len()), deposit);
+        assert_eq!(TemplateModule::claim_deposit(&claim), deposit);
+        assert_eq!(Balances::reserved_balance(1), deposit);
+        assert_eq!(Balances::free_balance(1), 100 - deposit);
+    })
+}
+
+#[test]
+fn create_claim_failed_when_balance_not_enough() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(TemplateModule::create_claim(Origin::signed(3), vec![0]));
+
+        assert_noop!(
+            TemplateModule::create_claim(Origin::signed(3), vec![1]),
+            Error::<Test>::InsufficientBalance
+        );
+        assert_noop!(
+            TemplateModule::create_claim(Origin::signed(4), vec![1]),
+            Error::<Test>::InsufficientBalance
+        );
+    })
+}
+
+#[test]
+fn revoke_claim_unreserves_deposit() {
+    new_test_ext().execute_with(|| {
+        let claim = vec![0, 1];
+        let _ = TemplateModule::create_claim(Origin::signed(1), claim.clone());
+
+        assert_ok!(TemplateModule::revoke_claim(Origin::signed(1), claim.clone()));
+
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 100);
+        assert!(!ClaimDeposits::<Test>::contains_key(&claim));
+    })
+}
+
+#[test]
+fn transfer_claim_moves_deposit() {
+    new_test_ext().execute_with(|| {
+        let claim = vec![0, 1];
+        let _ = TemplateModule::create_claim(Origin::signed(1), claim.clone());
+        let deposit = TemplateModule::claim_deposit(&claim);
+
+        assert_ok!(TemplateModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 100 - deposit);
+        assert_eq!(Balances::reserved_balance(2), deposit);
+
+        // 新的拥有者撤销存证时取回押金
+        assert_ok!(TemplateModule::revoke_claim(Origin::signed(2), claim.clone()));
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Balances::free_balance(2), 100 + deposit);
+    })
+}
+
+fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        System::set_block_number(System::block_number() + 1);
+        TemplateModule::on_initialize(System::block_number());
+    }
+}
+
+#[test]
+fn set_claim_metadata_works() {
+    new_test_ext().execute_with(|| {
+        let claim = vec![0, 1];
+        let _ = TemplateModule::create_claim(Origin::signed(1), claim.clone());
+
+        let memo = b"ipfs://x".to_vec();
+        assert_ok!(TemplateModule::set_claim_metadata(Origin::signed(1), claim.clone(), memo.clone(), Some(5)));
+
+        let info = TemplateModule::proofs(&claim).unwrap();
+        assert_eq!(info.memo, memo);
+        assert_eq!(info.expires_at, Some(5));
+        assert_eq!(TemplateModule::claim_expiries(5), vec![claim.clone()]);
+
+        // 押金按存证和备注的总长度计算
+        let deposit = TemplateModule::deposit_for(claim.len() + memo.len());
+        assert_eq!(TemplateModule::claim_deposit(&claim), deposit);
+        assert_eq!(Balances::reserved_balance(1), deposit);
+
+        // 清除备注和过期时间后押金和索引都恢复
+        assert_ok!(TemplateModule::set_claim_metadata(Origin::signed(1), claim.clone(), vec![], None));
+        assert_eq!(Balances::reserved_balance(1), TemplateModule::deposit_for(claim.len()));
+        assert!(!ClaimExpiries::<Test>::contains_key(5));
+    })
+}
+
+#[test]
+fn set_claim_metadata_failed_when_invalid() {
+    new_test_ext().execute_with(|| {
+        run_to_block(3);
+
+        let claim = vec![0, 1];
+        let _ = TemplateModule::create_claim(Origin::signed(1), claim.clone());
+
+        assert_noop!(
+            TemplateModule::set_claim_metadata(Origin::signed(2), claim.clone(), vec![], None),
+            Error::<Test>::NotClaimOwner
+        );
+        assert_noop!(
+            TemplateModule::set_claim_metadata(Origin::signed(1), claim.clone(), vec![0; 17], None),
+            Error::<Test>::MemoTooLong
+        );
+        assert_noop!(
+            TemplateModule::set_claim_metadata(Origin::signed(1), claim.clone(), vec![], Some(3)),
+            Error::<Test>::InvalidExpiry
+        );
+        assert_noop!(
+            TemplateModule::set_claim_metadata(Origin::signed(1), vec![9], vec![], None),
+            Error::<Test>::ClaimNotExist
+        );
+
+        let _ = TemplateModule::create_claim(Origin::signed(1), vec![2]);
+        let _ = TemplateModule::create_claim(Origin::signed(1), vec![3]);
+        assert_ok!(TemplateModule::set_claim_metadata(Origin::signed(1), vec![2], vec![], Some(10)));
+        assert_ok!(TemplateModule::set_claim_metadata(Origin::signed(1), vec![3], vec![], Some(10)));
+        assert_noop!(
+            TemplateModule::set_claim_metadata(Origin::signed(1), claim.clone(), vec![], Some(10)),
+            Error::<Test>::TooManyExpiries
+        );
+    })
+}
+
+#[test]
+fn expired_claims_are_pruned() {
+    new_test_ext().execute_with(|| {
+        let claim = vec![0, 1];
+        let _ = TemplateModule::create_claim(Origin::signed(1), claim.clone());
+        assert_ok!(TemplateModule::set_claim_metadata(Origin::signed(1), claim.clone(), vec![], Some(5)));
+
+        run_to_block(4);
+        assert!(TemplateModule::proofs(&claim).is_some());
+
+        run_to_block(5);
+        assert_eq!(TemplateModule::proofs(&claim), None);
+        assert!(!ClaimExpiries::<Test>::contains_key(5));
+        assert!(TemplateModule::claim_history(&claim).is_empty());
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 100);
+    })
+}
+
+#[test]
+fn claim_history_is_bounded() {
+    new_test_ext().execute_with(|| {
+        let claim = vec![0, 1];
+        let _ = TemplateModule::create_claim(Origin::signed(1), claim.clone());
+
+        run_to_block(1);
+        assert_ok!(TemplateModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+        run_to_block(2);
+        assert_ok!(TemplateModule::transfer_claim(Origin::signed(2), claim.clone(), 10));
+        run_to_block(3);
+        assert_ok!(TemplateModule::transfer_claim(Origin::signed(10), claim.clone(), 1));
+
+        assert_eq!(TemplateModule::claim_history(&claim), vec![(2, 1), (10, 2), (1, 3)]);
+        // 转移不改变创建区块
+        assert_eq!(TemplateModule::proof_of(&claim), Some((1, 0)));
+    })
+}
+
+#[test]
+fn migrate_to_v2_works() {
+    new_test_ext().execute_with(|| {
+        let claim = vec![0, 1];
+        let key = Proofs::<Test>::hashed_key_for(&claim);
+        frame_support::storage::unhashed::put(&key, &(7u64, 3u64));
+        StorageVersion::put(Releases::V1_0_0);
+
+        TemplateModule::on_runtime_upgrade();
+
+        assert_eq!(
+            TemplateModule::proofs(&claim),
+            Some(ClaimInfo { owner: 7, created_at: 3, memo: vec![], expires_at: None })
+        );
+        assert_eq!(TemplateModule::claim_history(&claim), vec![(7, 3)]);
+        assert_eq!(StorageVersion::get(), Releases::V2_0_0);
+    })
+}
+
+#[test]
+fn transfer_claim_deposits_event() {
+    new_test_ext().execute_with(|| {
+        // 创世区块不记录事件
+        run_to_block(1);
+
+        let claim = vec![0, 1];
+        let _ = TemplateModule::create_claim(Origin::signed(1), claim.clone());
+
+        assert_ok!(TemplateModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+
+        assert_eq!(
+            last_event(),
+            TestEvent::template(RawEvent::ClaimTransferred(1, 2, claim)),
+        );
+    })
+}
+
+#[test]
+fn transfer_claim_failed_when_transfer_to_self() {
+    new_test_ext().execute_with(|| {
+        let claim = vec![0, 1];
+        let _ = TemplateModule::create_claim(Origin::signed(1), claim.clone());
+
+        assert_noop!(
+            TemplateModule::transfer_claim(Origin::signed(1), claim.clone(), 1),
+            Error::<Test>::TransferToSelf
+        );
+        assert_noop!(
+            TemplateModule::offer_claim(Origin::signed(1), claim.clone(), 1),
+            Error::<Test>::TransferToSelf
+        );
+    })
+}
+
+#[test]
+fn offer_and_accept_claim_works() {
+    new_test_ext().execute_with(|| {
+        let claim = vec![0, 1];
+        let _ = TemplateModule::create_claim(Origin::signed(1), claim.clone());
+        let deposit = TemplateModule::claim_deposit(&claim);
+
+        assert_ok!(TemplateModule::offer_claim(Origin::signed(1), claim.clone(), 2));
+        assert_eq!(TemplateModule::claim_offer(&claim), Some(2));
+        // 提议不会立即转移所有权
+        assert_eq!(TemplateModule::proof_of(&claim).map(|(owner, _)| owner), Some(1));
+
+        assert_noop!(
+            TemplateModule::accept_claim(Origin::signed(10), claim.clone()),
+            Error::<Test>::NotOfferRecipient
+        );
+
+        assert_ok!(TemplateModule::accept_claim(Origin::signed(2), claim.clone()));
+        assert_eq!(TemplateModule::proof_of(&claim).map(|(owner, _)| owner), Some(2));
+        assert_eq!(TemplateModule::claim_offer(&claim), None);
+        assert_eq!(Balances::reserved_balance(2), deposit);
+
+        assert_noop!(
+            TemplateModule::accept_claim(Origin::signed(2), claim.clone()),
+            Error::<Test>::ClaimOfferNotExist
+        );
+    })
+}
+
+#[test]
+fn cancel_claim_offer_works() {
+    new_test_ext().execute_with(|| {
+        let claim = vec![0, 1];
+        let _ = TemplateModule::create_claim(Origin::signed(1), claim.clone());
+
+        assert_noop!(
+            TemplateModule::cancel_claim_offer(Origin::signed(1), claim.clone()),
+            Error::<Test>::ClaimOfferNotExist
+        );
+
+        assert_ok!(TemplateModule::offer_claim(Origin::signed(1), claim.clone(), 2));
+        assert_noop!(
+            TemplateModule::cancel_claim_offer(Origin::signed(2), claim.clone()),
+            Error::<Test>::NotClaimOwner
+        );
+
+        assert_ok!(TemplateModule::cancel_claim_offer(Origin::signed(1), claim.clone()));
+        assert_noop!(
+            TemplateModule::accept_claim(Origin::signed(2), claim.clone()),
+            Error::<Test>::ClaimOfferNotExist
+        );
+    })
+}
+
+#[test]
+fn revoke_claim_clears_offer() {
+    new_test_ext().execute_with(|| {
+        let claim = vec![0, 1];
+        let _ = TemplateModule::create_claim(Origin::signed(1), claim.clone());
+        assert_ok!(TemplateModule::offer_claim(Origin::signed(1), claim.clone(), 2));
+
+        assert_ok!(TemplateModule::revoke_claim(Origin::signed(1), claim.clone()));
+        assert_eq!(TemplateModule::claim_offer(&claim), None);
+    })
+}
+
+#[test]
+fn create_claims_batch_works() {
+    new_test_ext().execute_with(|| {
+        let claims = vec![vec![0], vec![1, 2], vec![3]];
+        assert_ok!(TemplateModule::create_claims_batch(Origin::signed(1), claims.clone()));
+
+        for claim in claims.iter() {
+            assert_eq!(TemplateModule::proof_of(claim).map(|(owner, _)| owner), Some(1));
+        }
+        let deposits: u64 = claims.iter().map(|c| TemplateModule::deposit_for(c.len())).sum();
+        assert_eq!(Balances::reserved_balance(1), deposits);
+    })
+}
+
+#[test]
+fn create_claims_batch_is_atomic() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            TemplateModule::create_claims_batch(Origin::signed(1), vec![vec![0], vec![1], vec![0]]),
+            Error::<Test>::ProofAlreadyExist
+        );
+        assert_noop!(
+            TemplateModule::create_claims_batch(Origin::signed(1), vec![vec![0], vec![0; 11]]),
+            Error::<Test>::ClaimTooLong
+        );
+        assert_noop!(
+            TemplateModule::create_claims_batch(Origin::signed(1), vec![vec![0], vec![1], vec![2], vec![3]]),
+            Error::<Test>::BatchTooLarge
+        );
+    })
+}
+
+fn leaves(n: u8) -> Vec<H256> {
+    (0..n).map(|i| TemplateModule::hash_claim(&[i])).collect()
+}
+
+// 为第 index 个叶子构造证明
+fn merkle_proof(leaves: &[H256], mut index: usize) -> Vec<H256> {
+    let mut proof = vec![];
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        let sibling = if index % 2 == 0 { index + 1 } else { index - 1 };
+        proof.push(*level.get(sibling).unwrap_or(&level[index]));
+        level = level
+            .chunks(2)
+            .map(|pair| TemplateModule::merkle_root(&[pair[0], *pair.get(1).unwrap_or(&pair[0])]))
+            .collect();
+        index /= 2;
+    }
+    proof
+}
+
+#[test]
+fn anchor_merkle_root_and_verify_inclusion_works() {
+    new_test_ext().execute_with(|| {
+        let leaves = leaves(5);
+        let root = TemplateModule::merkle_root(&leaves);
+
+        assert_noop!(
+            TemplateModule::anchor_merkle_root(Origin::signed(1), root, 0),
+            Error::<Test>::EmptyMerkleTree
+        );
+        assert_ok!(TemplateModule::anchor_merkle_root(Origin::signed(1), root, 5));
+        assert_eq!(TemplateModule::proof_of(root.as_ref()).map(|(owner, _)| owner), Some(1));
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = merkle_proof(&leaves, index);
+            assert!(TemplateModule::verify_inclusion(root, *leaf, index as u32, proof));
+        }
+
+        let proof = merkle_proof(&leaves, 1);
+        // 错误的位置、叶子或证明长度都不能通过验证
+        assert!(!TemplateModule::verify_inclusion(root, leaves[1], 2, proof.clone()));
+        assert!(!TemplateModule::verify_inclusion(root, leaves[2], 1, proof.clone()));
+        assert!(!TemplateModule::verify_inclusion(root, leaves[1], 1, proof[..2].to_vec()));
+        assert!(!TemplateModule::verify_inclusion(root, leaves[0], 5, merkle_proof(&leaves, 4)));
+
+        // 撤销后根不再被锚定
+        assert_ok!(TemplateModule::revoke_claim(Origin::signed(1), root.as_ref().to_vec()));
+        assert!(!TemplateModule::verify_inclusion(root, leaves[1], 1, proof));
+    })
+}
+
+#[test]
+fn merkle_depth_works() {
+    assert_eq!(TemplateModule::merkle_depth(0), 0);
+    assert_eq!(TemplateModule::merkle_depth(1), 0);
+    assert_eq!(TemplateModule::merkle_depth(2), 1);
+    assert_eq!(TemplateModule::merkle_depth(5), 3);
+    // 最大的叶子数量不会溢出
+    assert_eq!(TemplateModule::merkle_depth(u32::MAX), 32);
+}
+
+#[test]
+fn genesis_proofs_work() {
+    new_test_ext_with_proofs(vec![(1, vec![0, 1]), (2, vec![2])]).execute_with(|| {
+        assert_eq!(
+            Proofs::<Test>::get(&vec![0, 1]),
+            Some(ClaimInfo { owner: 1, created_at: 0, memo: vec![], expires_at: None })
+        );
+        assert_eq!(TemplateModule::claim_history(&vec![0, 1]), vec![(1, 0)]);
+        assert_eq!(TemplateModule::proof_of(&[2][..]).map(|(owner, _)| owner), Some(2));
+
+        // 创世存证和普通存证一样质押押金, 撤销时退还
+        assert_eq!(TemplateModule::claim_deposit(&vec![0, 1]), TemplateModule::deposit_for(2));
+        assert_eq!(Balances::reserved_balance(1), TemplateModule::deposit_for(2));
+        assert_eq!(Balances::reserved_balance(2), TemplateModule::deposit_for(1));
+
+        assert_ok!(TemplateModule::revoke_claim(Origin::signed(1), vec![0, 1]));
+        assert_eq!(Balances::reserved_balance(1), 0);
+    })
+}
+
+#[test]
+#[should_panic(expected = "duplicate genesis proof")]
+fn genesis_rejects_duplicate_proofs() {
+    new_test_ext_with_proofs(vec![(1, vec![0, 1]), (2, vec![0, 1])]);
+}
+
+#[test]
+#[should_panic(expected = "genesis proof owner cannot afford the deposit")]
+fn genesis_requires_proof_deposit() {
+    // 账户 3 只够支付最短存证的押金
+    new_test_ext_with_proofs(vec![(3, vec![0, 1, 2])]);
+}
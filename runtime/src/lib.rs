@@ -67,6 +67,12 @@ pub type Index = u32;
 /// A hash of some data used by the chain.
 pub type Hash = sp_core::H256;
 
+/// A timestamp: milliseconds since the unix epoch.
+pub type Moment = u64;
+
+/// Index of a kitty in the kitties pallet.
+pub type KittyIndex = u32;
+
 /// Digest item type.
 pub type DigestItem = generic::DigestItem<Hash>;
 
@@ -98,10 +104,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("node-template"),
 	impl_name: create_runtime_str!("node-template"),
 	authoring_version: 1,
-	spec_version: 1,
+	spec_version: 7,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 4,
 };
 
 pub const MILLISECS_PER_BLOCK: u64 = 6000;
@@ -224,7 +230,7 @@ parameter_types! {
 
 impl pallet_timestamp::Trait for Runtime {
 	/// A timestamp: milliseconds since the unix epoch.
-	type Moment = u64;
+	type Moment = Moment;
 	type OnTimestampSet = Aura;
 	type MinimumPeriod = MinimumPeriod;
 	type WeightInfo = ();
@@ -264,16 +270,66 @@ impl pallet_sudo::Trait for Runtime {
 	type Call = Call;
 }
 
+parameter_types! {
+	pub const MaxClaimLength: u32 = 256;
+	pub const ClaimDepositBase: Balance = 10_000;
+	pub const ClaimDepositPerByte: Balance = 100;
+	pub const MaxMemoLength: u32 = 128;
+	pub const MaxHistoryLength: u32 = 16;
+	pub const MaxExpiriesPerBlock: u32 = 64;
+	pub const MaxBatchSize: u32 = 100;
+}
+
 /// Configure the template pallet in pallets/template.
 impl pallet_template::Trait for Runtime {
 	type Event = Event;
+	type MaxClaimLength = MaxClaimLength;
+	type ClaimHashing = BlakeTwo256;
+	type Currency = Balances;
+	type ClaimDepositBase = ClaimDepositBase;
+	type ClaimDepositPerByte = ClaimDepositPerByte;
+	type MaxMemoLength = MaxMemoLength;
+	type MaxHistoryLength = MaxHistoryLength;
+	type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+	type MaxBatchSize = MaxBatchSize;
+	type WeightInfo = pallet_template::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const MinAncestryDistance: u32 = 2;
+	pub const BreedingCooldown: BlockNumber = 10 * MINUTES;
+	pub const KittyDeposit: Balance = 1_000;
+	pub const MaxAuctionsPerBlock: u32 = 100;
+	pub const MaxOffersPerBlock: u32 = 100;
+	pub const MarketplaceFee: Perbill = Perbill::from_percent(2);
+	pub const CreatorRoyalty: Perbill = Perbill::from_percent(1);
+	pub const MutationRate: Perbill = Perbill::from_percent(2);
+	pub const MaxNameLength: u32 = 32;
+	pub const NameDeposit: Balance = 500;
+	pub const MaxSireWhitelist: u32 = 16;
+	pub const MaxLeasesPerBlock: u32 = 100;
 }
 
 impl pallet_kitties::Trait for Runtime {
 	type Event = Event;
-	type KittyIndex = u32;
+	type KittyIndex = KittyIndex;
 	type Currency = Balances;
 	type Randomness = RandomnessCollectiveFlip;
+	type MinAncestryDistance = MinAncestryDistance;
+	type BreedingCooldown = BreedingCooldown;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+	type MaxOffersPerBlock = MaxOffersPerBlock;
+	type MarketplaceFee = MarketplaceFee;
+	// 还没有国库模块, 手续费直接销毁
+	type Treasury = ();
+	type CreatorRoyalty = CreatorRoyalty;
+	type KittyDeposit = KittyDeposit;
+	type MutationRate = MutationRate;
+	type MaxNameLength = MaxNameLength;
+	type NameDeposit = NameDeposit;
+	type MaxSireWhitelist = MaxSireWhitelist;
+	type MaxLeasesPerBlock = MaxLeasesPerBlock;
+	type WeightInfo = pallet_kitties::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -292,9 +348,9 @@ construct_runtime!(
 		TransactionPayment: pallet_transaction_payment::{Module, Storage},
 		Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
 		// Include the custom logic from the template pallet in the runtime.
-		TemplateModule: pallet_template::{Module, Call, Storage, Event<T>},
+		TemplateModule: pallet_template::{Module, Call, Config<T>, Storage, Event<T>},
 		// Substrate Kitties module
-		Kitties: pallet_kitties::{Module, Storage, Call, Event<T>},
+		Kitties: pallet_kitties::{Module, Storage, Call, Event<T>, Config<T>},
 	}
 );
 
@@ -455,6 +511,46 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_template_runtime_api::ProofOfExistenceApi<Block, AccountId, BlockNumber, Hash> for Runtime {
+		fn proof(claim: Vec<u8>) -> Option<(AccountId, BlockNumber)> {
+			TemplateModule::proof_of(&claim)
+		}
+
+		fn hash_claim(document: Vec<u8>) -> Hash {
+			TemplateModule::hash_claim(&document)
+		}
+
+		fn verify_inclusion(root: Hash, leaf: Hash, index: u32, proof: Vec<Hash>) -> bool {
+			TemplateModule::verify_inclusion(root, leaf, index, proof)
+		}
+	}
+
+	impl pallet_kitties_runtime_api::KittiesApi<Block, AccountId, KittyIndex, Balance> for Runtime {
+		fn kitty(id: KittyIndex) -> Option<pallet_kitties::KittyInfo<AccountId, KittyIndex, Balance>> {
+			Kitties::kitty_info(id)
+		}
+
+		fn kitties_of(account: AccountId, page: u32) -> Vec<pallet_kitties::KittyInfo<AccountId, KittyIndex, Balance>> {
+			Kitties::kitties_of(&account, page)
+		}
+
+		fn ancestors(id: KittyIndex, generations: u32) -> Vec<Vec<KittyIndex>> {
+			Kitties::ancestors(id, generations)
+		}
+
+		fn traits(id: KittyIndex) -> Option<pallet_kitties::KittyTraits> {
+			Kitties::kitty_traits(id)
+		}
+
+		fn rarity(id: KittyIndex) -> Option<u32> {
+			Kitties::rarity_of(id)
+		}
+
+		fn try_state() -> Result<(), Vec<u8>> {
+			Kitties::do_try_state().map_err(|e| e.as_bytes().to_vec())
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -484,6 +580,8 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+			add_benchmark!(params, batches, pallet_template, TemplateModule);
+			add_benchmark!(params, batches, pallet_kitties, Kitties);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)